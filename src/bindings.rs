@@ -0,0 +1,365 @@
+use crate::gamepad::Gamepad;
+use gr8::emulator::KEY_COUNT;
+use macroquad::input::KeyCode;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use toml::{Table, Value};
+
+/// Where the per-ROM bindings are kept, relative to the working directory.
+pub const BINDINGS_PATH: &str = "bindings.toml";
+/// The table holding the controller bindings, which can't be mistaken for a ROM hash.
+const GAMEPAD_TABLE: &str = "gamepad";
+
+/// The left side of a QWERTY keyboard laid out like the COSMAC VIP hex keypad:
+///
+/// 1 2 3 4      1 2 3 C
+/// Q W E R  ->  4 5 6 D
+/// A S D F      7 8 9 E
+/// Z X C V      A 0 B F
+#[rustfmt::skip]
+pub const DEFAULT_BINDINGS: [(KeyCode, u8); KEY_COUNT] = [
+    (KeyCode::Key1, 0x1), (KeyCode::Key2, 0x2), (KeyCode::Key3, 0x3), (KeyCode::Key4, 0xC),
+    (KeyCode::Q, 0x4), (KeyCode::W, 0x5), (KeyCode::E, 0x6), (KeyCode::R, 0xD),
+    (KeyCode::A, 0x7), (KeyCode::S, 0x8), (KeyCode::D, 0x9), (KeyCode::F, 0xE),
+    (KeyCode::Z, 0xA), (KeyCode::X, 0x0), (KeyCode::C, 0xB), (KeyCode::V, 0xF),
+];
+
+macro_rules! key_names {
+    ($($key:ident),* $(,)?) => {
+        &[$((stringify!($key), KeyCode::$key)),*]
+    };
+}
+
+/// The host keys that can be bound, by the name used in the bindings file.
+#[rustfmt::skip]
+const KEY_NAMES: &[(&str, KeyCode)] = key_names![
+    A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
+    Key0, Key1, Key2, Key3, Key4, Key5, Key6, Key7, Key8, Key9,
+    Kp0, Kp1, Kp2, Kp3, Kp4, Kp5, Kp6, Kp7, Kp8, Kp9,
+    Up, Down, Left, Right, Space, Enter, Tab, Backspace,
+    LeftShift, RightShift, LeftControl, RightControl, LeftAlt, RightAlt,
+    Comma, Period, Slash, Semicolon, Apostrophe, LeftBracket, RightBracket, Minus, Equal,
+];
+
+pub fn key_name(key_code: KeyCode) -> Option<&'static str> {
+    KEY_NAMES
+        .iter()
+        .find(|(_, k)| *k == key_code)
+        .map(|(name, _)| *name)
+}
+
+fn key_code(name: &str) -> Option<KeyCode> {
+    KEY_NAMES
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, key_code)| *key_code)
+}
+
+/// Reads the Chip-8 key that `name` is bound to, written as a hex digit.
+pub fn parse_key(name: &str, value: &Value) -> Result<u8, String> {
+    value
+        .as_str()
+        .and_then(|digit| u8::from_str_radix(digit, 16).ok())
+        .filter(|key| (*key as usize) < KEY_COUNT)
+        .ok_or(format!(
+            "{name} must be bound to a hex digit from \"0\" to \"F\"."
+        ))
+}
+
+/// Identifies a ROM in the bindings file by the 64-bit FNV-1a hash of its contents.
+pub fn rom_hash(rom: &[u8]) -> String {
+    let hash = rom.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    });
+
+    format!("{hash:016x}")
+}
+
+/// Which host keys press which Chip-8 keys. Several host keys may press the same Chip-8 key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bindings {
+    keys: Vec<(KeyCode, u8)>,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Bindings {
+            keys: DEFAULT_BINDINGS.to_vec(),
+        }
+    }
+}
+
+impl Bindings {
+    /// The state of the keypad, given which host keys are held down.
+    pub fn keypad(&self, is_down: impl Fn(KeyCode) -> bool) -> [bool; KEY_COUNT] {
+        let mut keys = [false; KEY_COUNT];
+
+        for &(key_code, key) in &self.keys {
+            keys[key as usize] |= is_down(key_code);
+        }
+
+        keys
+    }
+
+    fn from_table(table: &Table) -> Result<Self, String> {
+        let mut keys = Vec::new();
+
+        for (name, value) in table {
+            let key_code = key_code(name).ok_or(format!("Unknown host key {name}."))?;
+            keys.push((key_code, parse_key(name, value)?));
+        }
+
+        Ok(Bindings { keys })
+    }
+
+    fn to_table(&self) -> Table {
+        self.keys
+            .iter()
+            .filter_map(|&(key_code, key)| {
+                let name = key_name(key_code)?;
+                Some((name.to_string(), Value::from(format!("{key:X}"))))
+            })
+            .collect()
+    }
+}
+
+/// The bindings file: one table per ROM hash, mapping host key names to hex digits, e.g.
+///
+/// ```toml
+/// [0d4f6c2a9b3e8a71]
+/// Left = "4"
+/// Up = "5"
+/// Right = "6"
+/// Down = "7"
+/// ```
+///
+/// ROMs without a table use the [`DEFAULT_BINDINGS`]. Controllers are bound in a table of their own,
+/// shared by every ROM:
+///
+/// ```toml
+/// [gamepad]
+/// DPadUp = "5"
+/// South = "6"
+/// ```
+#[derive(Debug)]
+pub struct BindingsFile {
+    path: PathBuf,
+    roms: Table,
+}
+
+impl BindingsFile {
+    /// Reads the file at `path`, which is fine to be missing.
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, String> {
+        let path = path.into();
+        let roms = match fs::read_to_string(&path) {
+            Ok(text) => text
+                .parse()
+                .map_err(|e| format!("Invalid {}: {e}", path.display()))?,
+            Err(e) if e.kind() == ErrorKind::NotFound => Table::new(),
+            Err(e) => return Err(e.to_string()),
+        };
+
+        Ok(BindingsFile { path, roms })
+    }
+
+    pub fn bindings_for(&self, rom_hash: &str) -> Result<Bindings, String> {
+        match self.roms.get(rom_hash) {
+            Some(Value::Table(table)) => Bindings::from_table(table),
+            Some(_) => Err(format!("Bindings for {rom_hash} must be a table.")),
+            None => Ok(Bindings::default()),
+        }
+    }
+
+    /// The controller bindings, or the defaults when there are none.
+    #[cfg_attr(not(feature = "gamepad"), allow(dead_code))]
+    pub fn gamepad(&self) -> Result<Gamepad, String> {
+        match self.roms.get(GAMEPAD_TABLE) {
+            Some(Value::Table(table)) => Gamepad::from_table(table),
+            Some(_) => Err(format!("Bindings for {GAMEPAD_TABLE} must be a table.")),
+            None => Ok(Gamepad::default()),
+        }
+    }
+
+    pub fn set_bindings(&mut self, rom_hash: &str, bindings: &Bindings) {
+        self.roms
+            .insert(rom_hash.to_string(), Value::Table(bindings.to_table()));
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        fs::write(&self.path, self.roms.to_string()).map_err(|e| e.to_string())
+    }
+}
+
+/// Rebinds the keypad one Chip-8 key at a time, from 0 to F.
+#[derive(Debug, Default)]
+pub struct RebindMenu {
+    keys: Vec<(KeyCode, u8)>,
+}
+
+impl RebindMenu {
+    /// The Chip-8 key waiting for a host key.
+    pub fn next_key(&self) -> u8 {
+        self.keys.len() as u8
+    }
+
+    /// Binds the next Chip-8 key to `key_code`, returning the new bindings once every key is bound.
+    ///
+    /// Keys that can't be saved, or that are already bound to an earlier Chip-8 key, are ignored.
+    pub fn bind(&mut self, key_code: KeyCode) -> Option<Bindings> {
+        let taken = self.keys.iter().any(|(k, _)| *k == key_code);
+
+        if key_name(key_code).is_some() && !taken {
+            self.keys.push((key_code, self.next_key()));
+        }
+
+        if self.keys.len() == KEY_COUNT {
+            Some(Bindings {
+                keys: std::mem::take(&mut self.keys),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// The keypad the game sees: nothing is held while the rebind menu is open, so that the keys being
+/// bound don't reach it, e.g. completing a pending FX0A.
+pub fn game_keypad(keys: [bool; KEY_COUNT], rebind_menu: Option<&RebindMenu>) -> [bool; KEY_COUNT] {
+    match rebind_menu {
+        Some(_) => [false; KEY_COUNT],
+        None => keys,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gr8::emulator::{Emulator, EmulatorStatus, Opcode};
+    use std::env;
+
+    #[test]
+    fn default_bindings_cover_every_key() {
+        let keypad = Bindings::default().keypad(|_| true);
+
+        assert_eq!(keypad, [true; KEY_COUNT]);
+    }
+
+    #[test]
+    fn keypad_follows_the_host_keys() {
+        let keypad = Bindings::default().keypad(|k| k == KeyCode::X || k == KeyCode::V);
+
+        let mut expected = [false; KEY_COUNT];
+        expected[0x0] = true;
+        expected[0xF] = true;
+        assert_eq!(keypad, expected);
+    }
+
+    #[test]
+    fn rom_hash_is_stable() {
+        assert_eq!(rom_hash(b""), "cbf29ce484222325");
+        assert_eq!(rom_hash(b"a"), "af63dc4c8601ec8c");
+    }
+
+    #[test]
+    fn parses_bindings_for_a_rom() {
+        let path = env::temp_dir().join(format!("gr8-bindings-{}.toml", std::process::id()));
+        fs::write(
+            &path,
+            "[0123456789abcdef]\nUp = \"5\"\nLeft = \"4\"\nSpace = \"a\"\n",
+        )
+        .unwrap();
+
+        let file = BindingsFile::load(&path).unwrap();
+        let keypad = file
+            .bindings_for("0123456789abcdef")
+            .unwrap()
+            .keypad(|k| k == KeyCode::Up || k == KeyCode::Space || k == KeyCode::Q);
+
+        let mut expected = [false; KEY_COUNT];
+        expected[0x5] = true;
+        expected[0xA] = true;
+        assert_eq!(keypad, expected);
+        assert_eq!(
+            file.bindings_for("fedcba9876543210"),
+            Ok(Bindings::default())
+        );
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn rejects_invalid_bindings() {
+        let table: Table = "Up = \"G\"".parse().unwrap();
+        assert!(Bindings::from_table(&table).is_err());
+
+        let table: Table = "Hyper = \"1\"".parse().unwrap();
+        assert!(Bindings::from_table(&table).is_err());
+    }
+
+    #[test]
+    fn saved_bindings_load_back() {
+        let path = env::temp_dir().join(format!("gr8-bindings-saved-{}.toml", std::process::id()));
+        let bindings = Bindings {
+            keys: vec![(KeyCode::Left, 0x4), (KeyCode::Right, 0x6)],
+        };
+
+        let mut file = BindingsFile::load(&path).unwrap();
+        file.set_bindings("0123456789abcdef", &bindings);
+        file.save().unwrap();
+
+        let file = BindingsFile::load(&path).unwrap();
+        let loaded = file.bindings_for("0123456789abcdef").unwrap();
+        assert_eq!(
+            loaded.keypad(|k| k == KeyCode::Right),
+            bindings.keypad(|k| k == KeyCode::Right)
+        );
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn rebind_menu_binds_every_key_in_order() {
+        let mut menu = RebindMenu::default();
+
+        assert_eq!(menu.bind(KeyCode::Escape), None);
+        assert_eq!(menu.bind(KeyCode::A), None);
+        assert_eq!(menu.bind(KeyCode::A), None);
+        assert_eq!(menu.next_key(), 1);
+
+        for (_, key_code) in &KEY_NAMES[1..KEY_COUNT - 1] {
+            assert_eq!(menu.bind(*key_code), None);
+        }
+
+        let bindings = menu.bind(KeyCode::P).unwrap();
+        let mut expected = [false; KEY_COUNT];
+        expected[0x0] = true;
+        expected[0xF] = true;
+        assert_eq!(
+            bindings.keypad(|k| k == KeyCode::A || k == KeyCode::P),
+            expected
+        );
+    }
+
+    #[test]
+    fn keys_bound_in_the_rebind_menu_dont_reach_the_game() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::HaltAndStoreKeypressIntoRegister(3)])
+            .with_register_as(3, 0xFF);
+        let bindings = Bindings::default();
+        let menu = RebindMenu::default();
+
+        assert_eq!(emulator.update(), Ok(EmulatorStatus::Working));
+
+        let keys = bindings.keypad(|k| k == KeyCode::X);
+        emulator.set_keys(game_keypad(keys, Some(&menu)));
+        assert!(!emulator.is_key_down(0x0));
+
+        emulator.set_keys(game_keypad([false; KEY_COUNT], Some(&menu)));
+        assert_eq!(emulator.update(), Ok(EmulatorStatus::Waiting));
+        assert_eq!(emulator.registers()[3], 0xFF);
+
+        emulator.set_keys(game_keypad(keys, None));
+        assert!(emulator.is_key_down(0x0));
+    }
+}
@@ -0,0 +1,258 @@
+use crate::bindings::BINDINGS_PATH;
+use gr8::emulator::{LoadOptions, Quirks};
+use macroquad::color::Color;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: gr8 [OPTIONS] <ROM>
+
+Runs the Chip-8, Super Chip-8 or XO-Chip program in the file ROM.
+
+Options:
+  --speed <N>        Instructions per frame, 60 frames per second [default: depends on the quirks]
+  --quirks <NAME>    Interpreter to behave like: vip, chip48, schip or xochip
+                     [default: schip for .sc8, xochip for .xo8 and vip otherwise]
+  --scale <N>        Size of a low resolution pixel on the screen [default: 10]
+  --palette <NAME>   Colors: classic, amber or green, or 2 or 4 comma-separated hex colors
+                     for the pixels off and on, then both XO-Chip planes [default: classic]
+  --keymap <PATH>    Key bindings file [default: bindings.toml]
+  --load-address <HEX>
+                     Where the ROM is loaded and starts running, e.g. 600 for the ETI-660
+                     [default: 200]
+  --mute             Start with the sound muted, needs the audio feature
+  -h, --help         Print this help
+";
+
+/// The screen colors for each combination of the two XO-Chip planes: none, first, second and both.
+pub type Palette = [Color; 4];
+
+#[rustfmt::skip]
+const PALETTES: [(&str, [u32; 4]); 3] = [
+    ("classic", [0x000000, 0xFFFFFF, 0x999999, 0x4D4D4D]),
+    ("amber", [0x1A0F00, 0xFFB000, 0xB37B00, 0x664600]),
+    ("green", [0x0A1A0A, 0x33FF33, 0x22AA22, 0x115511]),
+];
+
+/// The size of a pixel when `--scale` isn't given.
+const DEFAULT_SCALE: u32 = 10;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub rom_path: PathBuf,
+    /// Overrides the instructions per frame of the quirks profile.
+    pub speed: Option<u32>,
+    /// Overrides the quirks guessed from the ROM extension.
+    pub quirks: Option<Quirks>,
+    pub scale: u32,
+    pub palette: Palette,
+    pub keymap: PathBuf,
+    pub load_options: LoadOptions,
+    /// Only has an effect with the `audio` feature.
+    pub mute: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Run(Options),
+    Help,
+}
+
+/// Parses the command line arguments, without the program name.
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut args = args.into_iter();
+    let mut rom_path = None;
+    let mut speed = None;
+    let mut quirks = None;
+    let mut scale = DEFAULT_SCALE;
+    let mut palette = palette_named("classic").unwrap();
+    let mut keymap = PathBuf::from(BINDINGS_PATH);
+    let mut load_options = LoadOptions::default();
+    let mut mute = false;
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("{arg} needs a value."));
+
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--speed" => speed = Some(parse_positive("--speed", &value()?)?),
+            "--quirks" => quirks = Some(parse_quirks(&value()?)?),
+            "--scale" => scale = parse_positive("--scale", &value()?)?,
+            "--palette" => palette = parse_palette(&value()?)?,
+            "--keymap" => keymap = PathBuf::from(value()?),
+            "--load-address" => load_options.load_address = parse_address(&value()?)?,
+            "--mute" => mute = true,
+            _ if arg.starts_with('-') => return Err(format!("Unknown option {arg}.")),
+            _ if rom_path.is_some() => return Err(format!("Unexpected argument {arg}.")),
+            _ => rom_path = Some(PathBuf::from(arg)),
+        }
+    }
+
+    Ok(Command::Run(Options {
+        rom_path: rom_path.ok_or("Missing the ROM to run.")?,
+        speed,
+        quirks,
+        scale,
+        palette,
+        keymap,
+        load_options,
+        mute,
+    }))
+}
+
+fn parse_positive(option: &str, value: &str) -> Result<u32, String> {
+    value
+        .parse()
+        .ok()
+        .filter(|n| *n > 0)
+        .ok_or(format!("{option} must be a positive number, not {value}."))
+}
+
+fn parse_address(value: &str) -> Result<u16, String> {
+    let hex = value.trim_start_matches("0x");
+
+    u16::from_str_radix(hex, 16)
+        .map_err(|_| format!("--load-address must be a hex address, not {value}."))
+}
+
+fn parse_quirks(name: &str) -> Result<Quirks, String> {
+    match name {
+        "vip" => Ok(Quirks::COSMAC_VIP),
+        "chip48" => Ok(Quirks::CHIP_48),
+        "schip" => Ok(Quirks::SCHIP),
+        "xochip" => Ok(Quirks::XO_CHIP),
+        _ => Err(format!(
+            "Unknown quirks {name}, expected vip, chip48, schip or xochip."
+        )),
+    }
+}
+
+fn palette_named(name: &str) -> Option<Palette> {
+    PALETTES
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, colors)| colors.map(Color::from_hex))
+}
+
+/// Accepts a palette name, or hex colors. With only two colors, both XO-Chip planes use the second.
+fn parse_palette(value: &str) -> Result<Palette, String> {
+    if let Some(palette) = palette_named(value) {
+        return Ok(palette);
+    }
+
+    let colors = value
+        .split(',')
+        .map(|hex| {
+            let hex = hex.trim().trim_start_matches('#');
+            u32::from_str_radix(hex, 16)
+                .ok()
+                .filter(|_| hex.len() == 6)
+                .map(Color::from_hex)
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or(format!("Invalid palette {value}."))?;
+
+    match colors[..] {
+        [off, on] => Ok([off, on, on, on]),
+        [off, first, second, both] => Ok([off, first, second, both]),
+        _ => Err(format!(
+            "A palette needs 2 or 4 colors, not {}.",
+            colors.len()
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Command, String> {
+        parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    fn options(args: &[&str]) -> Options {
+        match parse_args(args) {
+            Ok(Command::Run(options)) => options,
+            other => panic!("Expected options, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn defaults() {
+        let options = options(&["pong.ch8"]);
+
+        assert_eq!(options.rom_path, PathBuf::from("pong.ch8"));
+        assert_eq!(options.speed, None);
+        assert_eq!(options.quirks, None);
+        assert_eq!(options.scale, DEFAULT_SCALE);
+        assert_eq!(options.palette, palette_named("classic").unwrap());
+        assert_eq!(options.keymap, PathBuf::from(BINDINGS_PATH));
+        assert_eq!(options.load_options, LoadOptions::default());
+        assert!(!options.mute);
+    }
+
+    #[test]
+    fn every_option() {
+        let options = options(&[
+            "--speed",
+            "20",
+            "--quirks",
+            "chip48",
+            "--scale",
+            "4",
+            "--palette",
+            "amber",
+            "--keymap",
+            "keys.toml",
+            "--load-address",
+            "0x600",
+            "--mute",
+            "roms/ant.sc8",
+        ]);
+
+        assert_eq!(options.rom_path, PathBuf::from("roms/ant.sc8"));
+        assert_eq!(options.speed, Some(20));
+        assert_eq!(options.quirks, Some(Quirks::CHIP_48));
+        assert_eq!(options.scale, 4);
+        assert_eq!(options.palette, palette_named("amber").unwrap());
+        assert_eq!(options.keymap, PathBuf::from("keys.toml"));
+        assert_eq!(options.load_options, LoadOptions::ETI_660);
+        assert!(options.mute);
+    }
+
+    #[test]
+    fn help() {
+        assert_eq!(parse_args(&["pong.ch8", "--help"]), Ok(Command::Help));
+        assert_eq!(parse_args(&["-h"]), Ok(Command::Help));
+    }
+
+    #[test]
+    fn custom_palettes() {
+        let black = Color::from_hex(0x000000);
+        let white = Color::from_hex(0xFFFFFF);
+        let red = Color::from_hex(0xFF0000);
+
+        assert_eq!(
+            parse_palette("000000,#ffffff"),
+            Ok([black, white, white, white])
+        );
+        assert_eq!(
+            parse_palette("000000,ffffff,ff0000,000000"),
+            Ok([black, white, red, black])
+        );
+        assert!(parse_palette("000000").is_err());
+        assert!(parse_palette("000000,fff").is_err());
+        assert!(parse_palette("sepia").is_err());
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert!(parse_args(&[]).is_err());
+        assert!(parse_args(&["a.ch8", "b.ch8"]).is_err());
+        assert!(parse_args(&["--fast", "a.ch8"]).is_err());
+        assert!(parse_args(&["a.ch8", "--speed"]).is_err());
+        assert!(parse_args(&["a.ch8", "--speed", "0"]).is_err());
+        assert!(parse_args(&["a.ch8", "--scale", "big"]).is_err());
+        assert!(parse_args(&["a.ch8", "--quirks", "hp48"]).is_err());
+        assert!(parse_args(&["a.ch8", "--load-address", "10000"]).is_err());
+    }
+}
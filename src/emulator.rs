@@ -1,3 +1,19 @@
+mod audio;
+mod display;
+#[allow(clippy::module_inception)]
 mod emulator;
+mod error;
+mod flags;
+mod loader;
 mod opcode;
+mod quirks;
+mod random;
+pub use audio::*;
+pub use display::*;
 pub use emulator::*;
+pub use error::*;
+pub use flags::*;
+pub use loader::*;
+pub use opcode::{Opcode, ToBits, UnknownInstruction};
+pub use quirks::*;
+pub use random::*;
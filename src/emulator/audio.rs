@@ -0,0 +1,208 @@
+use super::emulator::Emulator;
+
+/// XO-Chip audio patterns are 16 bytes, played back as 128 one-bit samples.
+pub const AUDIO_PATTERN_SIZE: usize = 16;
+pub const AUDIO_PATTERN_BITS: usize = AUDIO_PATTERN_SIZE * 8;
+/// The pitch at which a pattern plays back at 4000 bits per second.
+pub const DEFAULT_PITCH: u8 = 64;
+
+/// The tone played for programs that never load a pattern, in Hz.
+pub const DEFAULT_BEEP_FREQUENCY: f32 = 440.0;
+pub const DEFAULT_VOLUME: f32 = 0.25;
+
+/// Returns how many pattern bits are played per second: 4000 * 2^((pitch - 64) / 48).
+pub fn playback_rate(pitch: u8) -> f32 {
+    4000.0 * libm::exp2f((pitch as f32 - DEFAULT_PITCH as f32) / 48.0)
+}
+
+/// Fills `samples` with PCM in [-1.0, 1.0] for the audio pattern of the emulator, at `sample_rate` Hz.
+///
+/// `phase` is the position in the pattern (in bits) to start from, and the position to continue
+/// from is returned so that consecutive buffers join up. The output is silent while the sound
+/// timer is zero or before any pattern was loaded with F002.
+pub fn generate_samples(
+    emulator: &Emulator,
+    sample_rate: u32,
+    phase: f32,
+    samples: &mut [f32],
+) -> f32 {
+    let pattern = match emulator.audio_pattern() {
+        Some(pattern) if emulator.sound_timer() > 0 => pattern,
+        _ => {
+            samples.fill(0.0);
+            return phase;
+        }
+    };
+
+    let step = playback_rate(emulator.pitch()) / sample_rate as f32;
+    let mut phase = phase;
+
+    for sample in samples.iter_mut() {
+        let bit = phase as usize % AUDIO_PATTERN_BITS;
+        let high = (pattern[bit / 8] >> (7 - bit % 8)) & 1 == 1;
+
+        *sample = if high { 1.0 } else { -1.0 };
+        phase = (phase + step) % AUDIO_PATTERN_BITS as f32;
+    }
+
+    phase
+}
+
+/// The buzzer of the original Chip-8: a square wave for as long as the sound timer runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Beeper {
+    /// The pitch of the tone, in Hz.
+    pub frequency: f32,
+    /// The amplitude of the samples, from 0.0 to 1.0.
+    pub volume: f32,
+    pub muted: bool,
+}
+
+impl Default for Beeper {
+    fn default() -> Self {
+        Self::new(DEFAULT_BEEP_FREQUENCY, DEFAULT_VOLUME)
+    }
+}
+
+impl Beeper {
+    pub fn new(frequency: f32, volume: f32) -> Self {
+        Beeper {
+            frequency,
+            volume,
+            muted: false,
+        }
+    }
+
+    pub fn toggle_mute(&mut self) {
+        self.muted = !self.muted;
+    }
+
+    /// Fills `samples` with the tone at `sample_rate` Hz, or silence while the sound timer is zero
+    /// or the beeper is muted.
+    ///
+    /// `phase` is the position in the wave (in periods, from 0.0 to 1.0) to start from, and the
+    /// position to continue from is returned so that consecutive buffers join up.
+    pub fn generate(
+        &self,
+        emulator: &Emulator,
+        sample_rate: u32,
+        phase: f32,
+        samples: &mut [f32],
+    ) -> f32 {
+        if self.muted || emulator.sound_timer() == 0 {
+            samples.fill(0.0);
+            return phase;
+        }
+
+        let step = self.frequency / sample_rate as f32;
+        let mut phase = phase;
+
+        for sample in samples.iter_mut() {
+            *sample = if phase < 0.5 {
+                self.volume
+            } else {
+                -self.volume
+            };
+            phase = (phase + step) % 1.0;
+        }
+
+        phase
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emulator::opcode::Opcode;
+
+    fn emulator_playing(pattern: [u8; AUDIO_PATTERN_SIZE], pitch: u8) -> Emulator {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![
+                Opcode::SetMemoryAddress(0x400),
+                Opcode::LoadAudioPattern,
+                Opcode::SetRegister(0, pitch),
+                Opcode::SetPitchToRegister(0),
+                Opcode::SetRegister(0, 10),
+                Opcode::SetSoundTimerToRegister(0),
+            ])
+            .with_memory_as(pattern.to_vec(), 0x400);
+
+        for _ in 0..6 {
+            emulator.update().unwrap();
+        }
+
+        emulator
+    }
+
+    #[test]
+    fn playback_rate_doubles_every_48_steps() {
+        assert_eq!(playback_rate(64), 4000.0);
+        assert_eq!(playback_rate(112), 8000.0);
+        assert_eq!(playback_rate(16), 2000.0);
+    }
+
+    #[test]
+    fn plays_one_bit_per_sample_at_the_playback_rate() {
+        let mut pattern = [0; AUDIO_PATTERN_SIZE];
+        pattern[0] = 0b1010_0000;
+        let emulator = emulator_playing(pattern, DEFAULT_PITCH);
+
+        let mut samples = [0.0; 4];
+        let phase = generate_samples(&emulator, 4000, 0.0, &mut samples);
+
+        assert_eq!(samples, [1.0, -1.0, 1.0, -1.0]);
+        assert_eq!(phase, 4.0);
+    }
+
+    #[test]
+    fn phase_wraps_around_the_pattern() {
+        let mut pattern = [0; AUDIO_PATTERN_SIZE];
+        pattern[0] = 0b1000_0000;
+        let emulator = emulator_playing(pattern, 112);
+
+        let mut samples = [0.0; 2];
+        let phase = generate_samples(&emulator, 4000, 126.0, &mut samples);
+
+        assert_eq!(samples, [-1.0, 1.0]);
+        assert_eq!(phase, 2.0);
+    }
+
+    #[test]
+    fn beeper_plays_a_square_wave_at_its_frequency_and_volume() {
+        let emulator = emulator_playing([0; AUDIO_PATTERN_SIZE], DEFAULT_PITCH);
+        let beeper = Beeper::new(1000.0, 0.5);
+
+        let mut samples = [0.0; 6];
+        let phase = beeper.generate(&emulator, 4000, 0.0, &mut samples);
+
+        assert_eq!(samples, [0.5, 0.5, -0.5, -0.5, 0.5, 0.5]);
+        assert_eq!(phase, 0.5);
+    }
+
+    #[test]
+    fn beeper_is_silent_when_muted_or_without_sound_timer() {
+        let mut beeper = Beeper::default();
+        let mut samples = [1.0; 4];
+
+        beeper.generate(&Emulator::new(), 4000, 0.0, &mut samples);
+        assert_eq!(samples, [0.0; 4]);
+
+        let emulator = emulator_playing([0; AUDIO_PATTERN_SIZE], DEFAULT_PITCH);
+        beeper.toggle_mute();
+        samples.fill(1.0);
+        beeper.generate(&emulator, 4000, 0.0, &mut samples);
+        assert_eq!(samples, [0.0; 4]);
+
+        beeper.toggle_mute();
+        beeper.generate(&emulator, 4000, 0.0, &mut samples);
+        assert_eq!(samples, [0.25, 0.25, 0.25, 0.25]);
+    }
+
+    #[test]
+    fn silent_without_sound_timer_or_pattern() {
+        let mut samples = [1.0; 4];
+        generate_samples(&Emulator::new(), 4000, 0.0, &mut samples);
+
+        assert_eq!(samples, [0.0; 4]);
+    }
+}
@@ -0,0 +1,191 @@
+pub const LORES_WIDTH: usize = 64;
+pub const LORES_HEIGHT: usize = 32;
+pub const HIRES_WIDTH: usize = 128;
+pub const HIRES_HEIGHT: usize = 64;
+/// XO-Chip has two bitplanes, which combine into four colors.
+pub const PLANE_COUNT: usize = 2;
+pub const ALL_PLANES: u8 = (1 << PLANE_COUNT) - 1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resolution {
+    /// 64x32 pixels, the original Chip-8 resolution.
+    Low,
+    /// 128x64 pixels, the Super Chip-8 extended resolution.
+    High,
+}
+
+/// A framebuffer large enough for the high resolution mode, of which only the
+/// top-left `width() x height()` pixels are in use at any given time.
+///
+/// Each pixel holds one bit per plane, so its value is also its color index.
+/// The operations taking a `planes` bitmask leave the other planes untouched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Display {
+    resolution: Resolution,
+    pixels: [[u8; HIRES_WIDTH]; HIRES_HEIGHT],
+}
+
+impl Default for Display {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Display {
+    pub fn new() -> Self {
+        Display {
+            resolution: Resolution::Low,
+            pixels: [[0; HIRES_WIDTH]; HIRES_HEIGHT],
+        }
+    }
+
+    pub fn resolution(&self) -> Resolution {
+        self.resolution
+    }
+
+    /// Switches resolution, clearing every plane in the process.
+    pub fn set_resolution(&mut self, resolution: Resolution) {
+        self.resolution = resolution;
+        self.clear(ALL_PLANES);
+    }
+
+    pub fn width(&self) -> usize {
+        match self.resolution {
+            Resolution::Low => LORES_WIDTH,
+            Resolution::High => HIRES_WIDTH,
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        match self.resolution {
+            Resolution::Low => LORES_HEIGHT,
+            Resolution::High => HIRES_HEIGHT,
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        self.pixels[y][x]
+    }
+
+    /// Flips the pixel at (x, y) on the given planes, returning whether any of them was turned off.
+    pub fn toggle(&mut self, x: usize, y: usize, planes: u8) -> bool {
+        let pixel = &mut self.pixels[y][x];
+        let collision = *pixel & planes != 0;
+        *pixel ^= planes;
+        collision
+    }
+
+    pub fn clear(&mut self, planes: u8) {
+        for row in self.pixels.iter_mut() {
+            for pixel in row.iter_mut() {
+                *pixel &= !planes;
+            }
+        }
+    }
+
+    pub fn scroll_down(&mut self, n: usize, planes: u8) {
+        self.shift(0, n as isize, planes);
+    }
+
+    pub fn scroll_up(&mut self, n: usize, planes: u8) {
+        self.shift(0, -(n as isize), planes);
+    }
+
+    pub fn scroll_right(&mut self, n: usize, planes: u8) {
+        self.shift(n as isize, 0, planes);
+    }
+
+    pub fn scroll_left(&mut self, n: usize, planes: u8) {
+        self.shift(-(n as isize), 0, planes);
+    }
+
+    /// Moves the visible pixels of the given planes by (dx, dy), filling the uncovered area with blank pixels.
+    fn shift(&mut self, dx: isize, dy: isize, planes: u8) {
+        let (width, height) = (self.width() as isize, self.height() as isize);
+        let previous = self.pixels;
+
+        for y in 0..height {
+            for x in 0..width {
+                let (sx, sy) = (x - dx, y - dy);
+                let in_bounds = (0..width).contains(&sx) && (0..height).contains(&sy);
+                let moved = if in_bounds {
+                    previous[sy as usize][sx as usize] & planes
+                } else {
+                    0
+                };
+
+                let pixel = &mut self.pixels[y as usize][x as usize];
+                *pixel = (*pixel & !planes) | moved;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_to_low_resolution() {
+        let display = Display::new();
+
+        assert_eq!(display.resolution(), Resolution::Low);
+        assert_eq!((display.width(), display.height()), (64, 32));
+    }
+
+    #[test]
+    fn switching_resolution_clears_the_screen() {
+        let mut display = Display::new();
+        display.toggle(3, 3, ALL_PLANES);
+        display.set_resolution(Resolution::High);
+
+        assert_eq!((display.width(), display.height()), (128, 64));
+        assert_eq!(display.get(3, 3), 0);
+    }
+
+    #[test]
+    fn scrolling_moves_pixels_and_blanks_the_edge() {
+        let mut display = Display::new();
+        display.toggle(0, 0, 1);
+        display.toggle(63, 31, 1);
+
+        display.scroll_down(1, 1);
+        assert_eq!(display.get(0, 0), 0);
+        assert_eq!(display.get(0, 1), 1);
+
+        display.scroll_right(4, 1);
+        assert_eq!(display.get(4, 1), 1);
+
+        display.scroll_left(8, 1);
+        assert_eq!(display.get(59, 31), 0);
+        for x in 0..display.width() {
+            assert_eq!(display.get(x, 1), 0);
+        }
+    }
+
+    #[test]
+    fn toggle_reports_pixels_turned_off() {
+        let mut display = Display::new();
+
+        assert!(!display.toggle(1, 2, 1));
+        assert!(display.toggle(1, 2, 1));
+        assert_eq!(display.get(1, 2), 0);
+    }
+
+    #[test]
+    fn planes_are_independent() {
+        let mut display = Display::new();
+
+        assert!(!display.toggle(1, 1, 0b01));
+        assert!(!display.toggle(1, 1, 0b10));
+        assert_eq!(display.get(1, 1), 0b11);
+
+        display.scroll_right(1, 0b10);
+        assert_eq!(display.get(1, 1), 0b01);
+        assert_eq!(display.get(2, 1), 0b10);
+
+        display.clear(0b01);
+        assert_eq!(display.get(1, 1), 0);
+        assert_eq!(display.get(2, 1), 0b10);
+    }
+}
@@ -1,48 +1,97 @@
-use super::opcode::Opcode;
+use super::audio::{AUDIO_PATTERN_SIZE, DEFAULT_PITCH};
+use super::display::{Display, PLANE_COUNT, Resolution};
+use super::error::EmulatorError;
+use super::flags::{FLAG_COUNT, FlagStorage, MemoryFlagStorage};
+use super::loader::{DEFAULT_LOAD_ADDRESS, LoadOptions};
+use super::opcode::{Opcode, UnknownInstruction};
+use super::quirks::{MemoryIncrement, Quirks};
+use super::random::{RandomSource, SeededRandomSource, default_random_source};
 use crate::emulator::opcode::ToBits;
-use rand::random_range;
-use std::fs;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::{io::Read, path::Path};
 
-pub const DISPLAY_WIDTH: usize = 64;
-pub const DISPLAY_HEIGHT: usize = 32;
-pub const MEMORY_SIZE: usize = 4096;
+pub const MEMORY_SIZE: usize = 0x10000;
 pub const REGISTER_COUNT: usize = 16;
 pub const STACK_SIZE: usize = 48;
+/// The hexadecimal keypad has keys 0 to F.
+pub const KEY_COUNT: usize = 16;
+pub const DEFAULT_INSTRUCTIONS_PER_FRAME: u32 = 10;
+const LONG_INSTRUCTION_PREFIX: (u8, u8) = (0xF0, 0x00);
 pub const FONT_DATA_ADDRESS: usize = 0x20;
+pub const FONT_SPRITE_SIZE: usize = 5;
+pub const LARGE_FONT_DATA_ADDRESS: usize = FONT_DATA_ADDRESS + 16 * FONT_SPRITE_SIZE;
+pub const LARGE_FONT_SPRITE_SIZE: usize = 10;
 
 #[derive(Debug)]
 pub struct Emulator {
-    pub display: [[u8; DISPLAY_WIDTH]; DISPLAY_HEIGHT],
+    pub display: Display,
     memory: [u8; MEMORY_SIZE],
     registers: [u8; REGISTER_COUNT],
     address: u16,
-    pub time_in_ms: u128,
+    instructions_per_frame: u32,
+    frame_cycles: u32,
     delay_timer: u8,
-    delay_timer_last_updated: u128,
     sound_timer: u8,
-    sound_timer_last_updated: u128,
-    input: [u8; 16],
+    input: [u8; KEY_COUNT],
     stack: [u16; 48],
     sp: usize,
     pc: usize,
-    awaiting_keypress: bool,
+    awaiting_key: Option<KeyWait>,
+    awaiting_vblank: bool,
+    planes: u8,
+    audio_pattern: Option<[u8; AUDIO_PATTERN_SIZE]>,
+    pitch: u8,
+    exited: bool,
+    quirks: Quirks,
+    memory_policy: MemoryPolicy,
+    random_source: Box<dyn RandomSource>,
+    flag_storage: Box<dyn FlagStorage>,
 }
 
 impl From<Vec<Opcode>> for Emulator {
     fn from(opcodes: Vec<Opcode>) -> Self {
-        let mut emulator = Emulator::new();
-        emulator
-            .load_instructions(opcodes.to_bits())
-            .expect("Can't load instructions.");
-        emulator
+        Emulator::new().with_opcodes(opcodes)
     }
 }
 
+/// FX0A in progress: the register to store the key into, and the key held down so far.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct KeyWait {
+    register: u8,
+    pressed: Option<u8>,
+}
+
 #[derive(Debug, PartialEq)]
 pub enum EmulatorStatus {
     Working,
     Waiting,
     Done,
+    /// An instruction accessed `addr` out of memory under [`MemoryPolicy::Trap`]. The program
+    /// counter points at that instruction, which runs again on the next update.
+    Trapped {
+        addr: usize,
+    },
+}
+
+/// What happens when a program accesses memory past the size allowed by its [`Quirks`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MemoryPolicy {
+    /// Addresses wrap around, like the address lines of the original hardware.
+    Wrap,
+    /// The update fails with [`EmulatorError::MemoryOutOfBounds`].
+    #[default]
+    Error,
+    /// The update stops before the faulting instruction and returns [`EmulatorStatus::Trapped`],
+    /// for a debugger to inspect.
+    Trap,
+}
+
+impl Default for Emulator {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Emulator {
@@ -51,17 +100,25 @@ impl Emulator {
             memory: [0; MEMORY_SIZE],
             registers: [0; REGISTER_COUNT],
             address: 0,
-            display: [[0; DISPLAY_WIDTH]; DISPLAY_HEIGHT],
+            display: Display::new(),
+            instructions_per_frame: DEFAULT_INSTRUCTIONS_PER_FRAME,
+            frame_cycles: 0,
             delay_timer: 0,
-            delay_timer_last_updated: 0,
             sound_timer: 0,
-            sound_timer_last_updated: 0,
-            time_in_ms: 0,
-            input: [0; 16],
+            input: [0; KEY_COUNT],
             stack: [0; STACK_SIZE],
             sp: 0,
-            pc: 0x200,
-            awaiting_keypress: false,
+            pc: DEFAULT_LOAD_ADDRESS as usize,
+            awaiting_key: None,
+            awaiting_vblank: false,
+            planes: 1,
+            audio_pattern: None,
+            pitch: DEFAULT_PITCH,
+            exited: false,
+            quirks: Quirks::default(),
+            memory_policy: MemoryPolicy::default(),
+            random_source: Box::new(default_random_source()),
+            flag_storage: Box::new(MemoryFlagStorage::default()),
         };
 
         emulator.init();
@@ -70,7 +127,7 @@ impl Emulator {
     }
 
     fn init(&mut self) {
-        let font_data: [[u8; 5]; 16] = [
+        let font_data: [[u8; FONT_SPRITE_SIZE]; 16] = [
             [0xF0, 0x90, 0x90, 0x90, 0xF0], // 0
             [0x20, 0x60, 0x20, 0x20, 0x70], // 1
             [0xF0, 0x10, 0xF0, 0x80, 0xF0], // 2
@@ -89,67 +146,294 @@ impl Emulator {
             [0xF0, 0x80, 0xF0, 0x80, 0x80], // F
         ];
 
-        for letter_idx in 0..font_data.len() {
-            for byte_idx in 0..font_data[letter_idx].len() {
-                self.memory[FONT_DATA_ADDRESS + letter_idx * 5 + byte_idx] =
-                    font_data[letter_idx][byte_idx];
-            }
+        // Super Chip-8 only ships the digits, A-F follow the same 8x10 style.
+        let large_font_data: [[u8; LARGE_FONT_SPRITE_SIZE]; 16] = [
+            [0x3C, 0x7E, 0xE7, 0xC3, 0xC3, 0xC3, 0xC3, 0xE7, 0x7E, 0x3C], // 0
+            [0x18, 0x38, 0x58, 0x18, 0x18, 0x18, 0x18, 0x18, 0x18, 0x3C], // 1
+            [0x3E, 0x7F, 0xC3, 0x06, 0x0C, 0x18, 0x30, 0x60, 0xFF, 0xFF], // 2
+            [0x3C, 0x7E, 0xC3, 0x03, 0x0E, 0x0E, 0x03, 0xC3, 0x7E, 0x3C], // 3
+            [0x06, 0x0E, 0x1E, 0x36, 0x66, 0xC6, 0xFF, 0xFF, 0x06, 0x06], // 4
+            [0xFF, 0xFF, 0xC0, 0xC0, 0xFC, 0xFE, 0x03, 0xC3, 0x7E, 0x3C], // 5
+            [0x3E, 0x7C, 0xC0, 0xC0, 0xFC, 0xFE, 0xC3, 0xC3, 0x7E, 0x3C], // 6
+            [0xFF, 0xFF, 0x03, 0x06, 0x0C, 0x18, 0x30, 0x60, 0x60, 0x60], // 7
+            [0x3C, 0x7E, 0xC3, 0xC3, 0x7E, 0x7E, 0xC3, 0xC3, 0x7E, 0x3C], // 8
+            [0x3C, 0x7E, 0xC3, 0xC3, 0x7F, 0x3F, 0x03, 0x03, 0x3E, 0x7C], // 9
+            [0x3C, 0x7E, 0xC3, 0xC3, 0xFF, 0xFF, 0xC3, 0xC3, 0xC3, 0xC3], // A
+            [0xFC, 0xFE, 0xC3, 0xC3, 0xFE, 0xFE, 0xC3, 0xC3, 0xFE, 0xFC], // B
+            [0x3C, 0x7E, 0xC3, 0xC0, 0xC0, 0xC0, 0xC0, 0xC3, 0x7E, 0x3C], // C
+            [0xFC, 0xFE, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xC3, 0xFE, 0xFC], // D
+            [0xFF, 0xFF, 0xC0, 0xC0, 0xFC, 0xFC, 0xC0, 0xC0, 0xFF, 0xFF], // E
+            [0xFF, 0xFF, 0xC0, 0xC0, 0xFC, 0xFC, 0xC0, 0xC0, 0xC0, 0xC0], // F
+        ];
+
+        for (letter_idx, letter) in font_data.iter().enumerate() {
+            let address = FONT_DATA_ADDRESS + letter_idx * FONT_SPRITE_SIZE;
+            self.memory[address..address + FONT_SPRITE_SIZE].copy_from_slice(letter);
+        }
+
+        for (letter_idx, letter) in large_font_data.iter().enumerate() {
+            let address = LARGE_FONT_DATA_ADDRESS + letter_idx * LARGE_FONT_SPRITE_SIZE;
+            self.memory[address..address + LARGE_FONT_SPRITE_SIZE].copy_from_slice(letter);
         }
     }
 
-    fn with_opcodes(mut self, opcodes: Vec<Opcode>) -> Self {
-        self.load_instructions(opcodes.to_bits()).unwrap();
+    /// Sets how many instructions make up a frame, i.e. one tick of the timers, for both `update`
+    /// and `run_frame`.
+    pub fn with_instructions_per_frame(mut self, instructions_per_frame: u32) -> Self {
+        self.instructions_per_frame = instructions_per_frame.max(1);
         self
     }
 
-    fn with_address_as(mut self, address: u16) -> Self {
-        self.address = address;
+    pub fn with_quirks(mut self, quirks: Quirks) -> Self {
+        self.quirks = quirks;
         self
     }
 
-    fn with_memory_as(mut self, memory: Vec<u8>, address: u16) -> Self {
-        for i in 0..memory.len() {
-            self.memory[(address + i as u16) as usize] = memory[i as usize];
+    pub fn with_memory_policy(mut self, memory_policy: MemoryPolicy) -> Self {
+        self.memory_policy = memory_policy;
+        self
+    }
+
+    /// Makes CXNN reproducible, the same seed always yields the same numbers.
+    pub fn with_seed(self, seed: u64) -> Self {
+        self.with_random_source(SeededRandomSource::new(seed))
+    }
+
+    pub fn with_random_source(mut self, random_source: impl RandomSource + 'static) -> Self {
+        self.random_source = Box::new(random_source);
+        self
+    }
+
+    pub fn with_flag_storage(mut self, flag_storage: impl FlagStorage + 'static) -> Self {
+        self.flag_storage = Box::new(flag_storage);
+        self
+    }
+
+    /// Loads the assembled opcodes as the program, at 0x200. An empty program leaves memory as is.
+    ///
+    /// # Panics
+    ///
+    /// If the program doesn't fit in the memory size of the quirks, see [`Emulator::load_bytes`].
+    pub fn with_opcodes(mut self, opcodes: Vec<Opcode>) -> Self {
+        if !opcodes.is_empty() {
+            self.load_bytes(&opcodes.to_bits(), LoadOptions::default())
+                .expect("The program doesn't fit in memory.");
         }
         self
     }
 
-    fn with_register_as(mut self, r: u8, v: u8) -> Self {
-        self.registers[r as usize] = v;
+    /// Sets the address register I.
+    pub fn with_address_as(mut self, address: u16) -> Self {
+        self.address = address;
+        self
+    }
+
+    /// Copies `memory` into memory, starting at `address`, which must leave room for all of it.
+    #[cfg(test)]
+    pub(crate) fn with_memory_as(mut self, memory: Vec<u8>, address: u16) -> Self {
+        let address = address as usize;
+        self.memory[address..address + memory.len()].copy_from_slice(&memory);
+        self
+    }
+
+    /// Sets the register VR (0 to F) to `v`.
+    pub fn with_register_as(mut self, r: u8, v: u8) -> Self {
+        self.registers[(r & 0xF) as usize] = v;
         self
     }
 
-    fn with_input_as(mut self, r: u8, v: u8) -> Self {
-        self.input[r as usize] = v;
+    /// Sets the key `r` (0 to F) as held down when `v` isn't zero, without completing a pending FX0A.
+    pub fn with_input_as(mut self, r: u8, v: u8) -> Self {
+        self.input[(r & 0xF) as usize] = v;
         self
     }
 
-    fn with_display(mut self, display: [[u8; 64]; 32]) -> Self {
+    pub fn with_display(mut self, display: Display) -> Self {
         self.display = display;
         self
     }
 
-    fn load_instructions(&mut self, instructions: Vec<u8>) -> Result<(), String> {
-        for i in 0..instructions.len() {
-            self.memory[i + 0x200] = instructions[i];
+    /// Copies the program to its load address and jumps to its entry point, e.g. a ROM downloaded
+    /// into memory or embedded with [`include_rom!`](crate::include_rom).
+    ///
+    /// The program must fit in the memory size of the quirks, so set them before loading.
+    pub fn load_bytes(
+        &mut self,
+        instructions: &[u8],
+        options: LoadOptions,
+    ) -> Result<(), EmulatorError> {
+        let memory_size = self.quirks.memory_size.clamp(1, MEMORY_SIZE);
+        let load_address = options.load_address as usize;
+        let entry_point = options.entry_point() as usize;
+        let max = memory_size.saturating_sub(load_address);
+
+        if instructions.is_empty() {
+            return Err(EmulatorError::EmptyRom);
+        }
+        if instructions.len() > max {
+            return Err(EmulatorError::RomTooLarge {
+                size: instructions.len(),
+                max,
+            });
+        }
+        if entry_point >= memory_size {
+            return Err(EmulatorError::MemoryOutOfBounds { addr: entry_point });
         }
+
+        self.memory[load_address..load_address + instructions.len()].copy_from_slice(instructions);
+        self.pc = entry_point;
         Ok(())
     }
 
-    pub fn load_rom(&mut self, path_to_rom: &str) -> Result<(), String> {
-        let rom_data = fs::read(path_to_rom).map_err(|e| e.to_string())?;
-        self.load_instructions(rom_data)?;
+    /// Loads the ROM at `path_to_rom` at 0x200.
+    #[cfg(feature = "std")]
+    pub fn load_rom(&mut self, path_to_rom: impl AsRef<Path>) -> Result<(), EmulatorError> {
+        self.load_rom_with(path_to_rom, LoadOptions::default())
+    }
 
-        Ok(())
+    /// Loads the ROM at `path_to_rom` where `options` say, e.g. [`LoadOptions::ETI_660`].
+    #[cfg(feature = "std")]
+    pub fn load_rom_with(
+        &mut self,
+        path_to_rom: impl AsRef<Path>,
+        options: LoadOptions,
+    ) -> Result<(), EmulatorError> {
+        let rom_data = std::fs::read(path_to_rom)?;
+        self.load_bytes(&rom_data, options)
+    }
+
+    /// Loads the ROM read from `reader` until its end, e.g. a file or a network stream.
+    ///
+    /// Reading stops just past the size of memory, so an endless stream fails with
+    /// [`EmulatorError::RomTooLarge`] rather than filling up the host's memory.
+    #[cfg(feature = "std")]
+    pub fn load_reader(
+        &mut self,
+        reader: impl Read,
+        options: LoadOptions,
+    ) -> Result<(), EmulatorError> {
+        let mut rom_data = Vec::new();
+        reader
+            .take(MEMORY_SIZE as u64 + 1)
+            .read_to_end(&mut rom_data)?;
+        self.load_bytes(&rom_data, options)
+    }
+
+    /// V0 to VF.
+    pub fn registers(&self) -> &[u8; REGISTER_COUNT] {
+        &self.registers
+    }
+
+    pub fn memory(&self) -> &[u8; MEMORY_SIZE] {
+        &self.memory
+    }
+
+    /// The address of the next instruction.
+    pub fn pc(&self) -> u16 {
+        self.pc as u16
+    }
+
+    /// The address register I.
+    pub fn address(&self) -> u16 {
+        self.address
+    }
+
+    /// The return addresses of the subroutines being run, innermost last.
+    pub fn stack(&self) -> &[u16] {
+        &self.stack[..self.sp]
+    }
+
+    pub fn delay_timer(&self) -> u8 {
+        self.delay_timer
+    }
+
+    pub fn sound_timer(&self) -> u8 {
+        self.sound_timer
+    }
+
+    pub fn is_key_down(&self, key: u8) -> bool {
+        self.input[(key & 0xF) as usize] != 0
+    }
+
+    pub fn quirks(&self) -> Quirks {
+        self.quirks
+    }
+
+    pub fn memory_policy(&self) -> MemoryPolicy {
+        self.memory_policy
+    }
+
+    pub fn instructions_per_frame(&self) -> u32 {
+        self.instructions_per_frame
+    }
+
+    /// The pattern last loaded by F002, if any.
+    pub fn audio_pattern(&self) -> Option<&[u8; AUDIO_PATTERN_SIZE]> {
+        self.audio_pattern.as_ref()
+    }
+
+    pub fn pitch(&self) -> u8 {
+        self.pitch
+    }
+
+    /// Marks `key` (0 to F) as held down.
+    pub fn press_key(&mut self, key: u8) {
+        let key = key & 0xF;
+        self.input[key as usize] = 1;
+
+        if let Some(wait) = self.awaiting_key.as_mut() {
+            wait.pressed.get_or_insert(key);
+        }
+    }
+
+    /// Marks `key` (0 to F) as released, which completes a pending FX0A if it was the key pressed.
+    pub fn release_key(&mut self, key: u8) {
+        let key = key & 0xF;
+        self.input[key as usize] = 0;
+
+        if let Some(wait) = self.awaiting_key
+            && wait.pressed == Some(key)
+        {
+            self.registers[wait.register as usize] = key;
+            self.awaiting_key = None;
+        }
+    }
+
+    /// Updates the whole keypad at once, e.g. from a snapshot of the host keyboard, pressing and
+    /// releasing only the keys that changed.
+    pub fn set_keys(&mut self, keys: [bool; KEY_COUNT]) {
+        for (key, down) in keys.into_iter().enumerate() {
+            let was_down = self.input[key] != 0;
+
+            if down && !was_down {
+                self.press_key(key as u8);
+            } else if !down && was_down {
+                self.release_key(key as u8);
+            }
+        }
     }
 
     fn goto(&mut self, address: u16) {
         self.pc = address as usize;
+        self.wrap_pc();
     }
 
-    fn call_subroutine(&mut self, address: u16) -> Result<(), String> {
+    fn advance_pc(&mut self, bytes: usize) {
+        self.pc += bytes;
+        self.wrap_pc();
+    }
+
+    /// Brings the program counter back into memory under [`MemoryPolicy::Wrap`]. Otherwise it's
+    /// left past the end, for the next fetch to fault.
+    fn wrap_pc(&mut self) {
+        if let Ok(pc) = self.memory_index(self.pc) {
+            self.pc = pc;
+        }
+    }
+
+    fn call_subroutine(&mut self, address: u16) -> Result<(), EmulatorError> {
         if self.sp >= self.stack.len() {
-            return Err("Stack overflow!".to_string());
+            return Err(EmulatorError::StackOverflow);
         }
 
         self.stack[self.sp] = self.pc as u16;
@@ -160,9 +444,9 @@ impl Emulator {
         Ok(())
     }
 
-    fn r#return(&mut self) -> Result<(), String> {
+    fn r#return(&mut self) -> Result<(), EmulatorError> {
         if self.sp == 0 {
-            return Err("Not in a subroutine!".to_string());
+            return Err(EmulatorError::StackUnderflow);
         }
 
         self.goto(self.stack[self.sp - 1]);
@@ -171,58 +455,256 @@ impl Emulator {
         Ok(())
     }
 
-    fn fetch_and_decode(&mut self) -> Result<Opcode, String> {
-        let instruction = (self.memory[self.pc], self.memory[self.pc + 1]);
-        self.pc += 2;
-        Ok(Opcode::decode(instruction)?)
+    fn fetch_and_decode(&mut self) -> Result<Opcode, EmulatorError> {
+        let pc = self.pc;
+        let instruction = self.fetch_word(pc)?;
+        self.advance_pc(2);
+
+        if instruction == LONG_INSTRUCTION_PREFIX {
+            let address = u16::from_be_bytes(self.fetch_word(self.pc)?.into());
+            self.advance_pc(2);
+            return Ok(Opcode::SetLongMemoryAddress(address));
+        }
+
+        Opcode::decode(instruction).map_err(|UnknownInstruction { bits }| {
+            EmulatorError::UnknownOpcode {
+                pc: pc as u16,
+                bits,
+            }
+        })
+    }
+
+    fn fetch_word(&self, address: usize) -> Result<(u8, u8), EmulatorError> {
+        Ok((self.read_memory(address)?, self.read_memory(address + 1)?))
+    }
+
+    /// Maps `address` into the memory allowed by the quirks, according to the memory policy.
+    fn memory_index(&self, address: usize) -> Result<usize, EmulatorError> {
+        let size = self.quirks.memory_size.clamp(1, MEMORY_SIZE);
+
+        match self.memory_policy {
+            _ if address < size => Ok(address),
+            MemoryPolicy::Wrap => Ok(address % size),
+            MemoryPolicy::Error | MemoryPolicy::Trap => {
+                Err(EmulatorError::MemoryOutOfBounds { addr: address })
+            }
+        }
+    }
+
+    /// Checks the `len` bytes from `address` up front, so that a faulting instruction has no effect.
+    fn check_memory(&self, address: usize, len: usize) -> Result<(), EmulatorError> {
+        if len > 0 {
+            self.memory_index(address + len - 1)?;
+        }
+        Ok(())
+    }
+
+    fn read_memory(&self, address: usize) -> Result<u8, EmulatorError> {
+        Ok(self.memory[self.memory_index(address)?])
+    }
+
+    fn write_memory(&mut self, address: usize, value: u8) -> Result<(), EmulatorError> {
+        self.memory[self.memory_index(address)?] = value;
+        Ok(())
+    }
+
+    /// Skips the next instruction, which is 4 bytes long in the case of F000 NNNN.
+    ///
+    /// A next instruction out of memory is skipped as a short one, fetching it faults anyway.
+    fn skip_instruction(&mut self) {
+        if self.fetch_word(self.pc) == Ok(LONG_INSTRUCTION_PREFIX) {
+            self.advance_pc(4);
+        } else {
+            self.advance_pc(2);
+        }
+    }
+
+    fn shift_source(&self, r0: u8, r1: u8) -> u8 {
+        match self.quirks.shift_uses_vy {
+            true => self.registers[r1 as usize],
+            false => self.registers[r0 as usize],
+        }
+    }
+
+    fn reset_flag_register(&mut self) {
+        if self.quirks.vf_reset {
+            self.registers[15] = 0;
+        }
+    }
+
+    fn increment_address_after_dump(&mut self, r0: u8) {
+        let increment = match self.quirks.memory_increment {
+            MemoryIncrement::None => 0,
+            MemoryIncrement::ByX => r0 as u16,
+            MemoryIncrement::ByXPlusOne => r0 as u16 + 1,
+        };
+        self.address = self.address.wrapping_add(increment);
+    }
+
+    /// How many flags FX75 and FX85 access, which must be no more than the quirks have.
+    fn flag_count(&self, r0: u8) -> Result<usize, EmulatorError> {
+        let count = r0 as usize + 1;
+        let max = self.quirks.flag_count.min(FLAG_COUNT);
+
+        if count > max {
+            return Err(EmulatorError::TooManyFlags { count, max });
+        }
+        Ok(count)
+    }
+
+    /// Yields the registers from VX to VY, walking backwards when X > Y.
+    fn register_range(r0: u8, r1: u8) -> impl Iterator<Item = usize> {
+        let (r0, r1) = (r0 as usize, r1 as usize);
+
+        (0..=r0.abs_diff(r1)).map(move |offset| match r0 <= r1 {
+            true => r0 + offset,
+            false => r0 - offset,
+        })
     }
 
-    fn update_timers(&mut self) {
-        if self.time_in_ms < 17 {
-            return;
+    /// Super Chip-8 1.1 scrolls by high resolution pixels, i.e. half as far in low resolution.
+    fn scroll_distance(&self, pixels: usize) -> usize {
+        match self.display.resolution() {
+            Resolution::Low if self.quirks.half_pixel_scroll => pixels / 2,
+            _ => pixels,
         }
+    }
+
+    /// Runs the 60Hz tick, which is also the vertical blank of the display.
+    fn tick_timers(&mut self) {
+        self.delay_timer = self.delay_timer.saturating_sub(1);
+        self.sound_timer = self.sound_timer.saturating_sub(1);
+        self.awaiting_vblank = false;
+        self.frame_cycles = 0;
+    }
+
+    /// Executes a single instruction, ticking the timers once every `instructions_per_frame` updates.
+    ///
+    /// Time only advances with the updates, so the same inputs always lead to the same state.
+    pub fn update(&mut self) -> Result<EmulatorStatus, EmulatorError> {
+        let status = self.step()?;
 
-        if self.delay_timer > 0 && self.time_in_ms - self.delay_timer_last_updated >= 17 {
-            self.delay_timer -= 1;
-            self.delay_timer_last_updated = self.time_in_ms;
+        self.frame_cycles += 1;
+        if self.frame_cycles >= self.instructions_per_frame {
+            self.tick_timers();
         }
 
-        if self.sound_timer > 0 && self.time_in_ms - self.sound_timer_last_updated >= 17 {
-            self.sound_timer -= 1;
-            self.sound_timer_last_updated = self.time_in_ms;
+        Ok(status)
+    }
+
+    /// Runs up to [`instructions_per_frame`](Emulator::instructions_per_frame) instructions, then
+    /// ticks the timers exactly once.
+    ///
+    /// The frame ends early when the emulator starts waiting (for a key or the vertical blank)
+    /// or is done. Calling this at 60Hz runs programs at the same speed on any host.
+    pub fn run_frame(&mut self) -> Result<EmulatorStatus, EmulatorError> {
+        let mut status = EmulatorStatus::Working;
+
+        for _ in 0..self.instructions_per_frame {
+            status = self.step()?;
+
+            if status != EmulatorStatus::Working {
+                break;
+            }
         }
+
+        self.tick_timers();
+
+        Ok(status)
     }
 
-    pub fn update(&mut self) -> Result<EmulatorStatus, String> {
-        self.update_timers();
+    /// Executes a single instruction without touching the timers.
+    fn step(&mut self) -> Result<EmulatorStatus, EmulatorError> {
+        if self.exited {
+            return Ok(EmulatorStatus::Done);
+        }
 
-        if self.awaiting_keypress {
+        if self.awaiting_key.is_some() || self.awaiting_vblank {
             return Ok(EmulatorStatus::Waiting);
         }
 
-        let opcode = self.fetch_and_decode()?;
+        let pc = self.pc;
+        let result = self
+            .fetch_and_decode()
+            .and_then(|opcode| self.execute(pc, opcode));
+
+        match result {
+            Err(EmulatorError::MemoryOutOfBounds { addr })
+                if self.memory_policy == MemoryPolicy::Trap =>
+            {
+                self.pc = pc;
+                Ok(EmulatorStatus::Trapped { addr })
+            }
+            result => result,
+        }
+    }
 
+    /// Executes `opcode`, fetched from `pc`.
+    fn execute(&mut self, pc: usize, opcode: Opcode) -> Result<EmulatorStatus, EmulatorError> {
         match opcode {
-            Opcode::ClearScreen => {
-                let this = &mut *self;
-                this.display = [[0; 64]; 32]
+            Opcode::ClearScreen => self.display.clear(self.planes),
+            Opcode::ScrollDown(immediate) => {
+                let pixels = self.scroll_distance(immediate as usize);
+                self.display.scroll_down(pixels, self.planes);
+            }
+            Opcode::ScrollUp(immediate) => {
+                let pixels = self.scroll_distance(immediate as usize);
+                self.display.scroll_up(pixels, self.planes);
+            }
+            Opcode::ScrollRight => {
+                let pixels = self.scroll_distance(4);
+                self.display.scroll_right(pixels, self.planes);
+            }
+            Opcode::ScrollLeft => {
+                let pixels = self.scroll_distance(4);
+                self.display.scroll_left(pixels, self.planes);
+            }
+            Opcode::DisableHighResolution => self.display.set_resolution(Resolution::Low),
+            Opcode::EnableHighResolution => self.display.set_resolution(Resolution::High),
+            Opcode::Exit => {
+                self.exited = true;
+                return Ok(EmulatorStatus::Done);
+            }
+            Opcode::Goto(address) => {
+                // Jumping onto the jump itself is the usual way for a ROM to halt.
+                let halted = address as usize == pc;
+                self.goto(address);
+
+                if halted {
+                    return Ok(EmulatorStatus::Done);
+                }
             }
-            Opcode::Goto(address) => self.goto(address),
             Opcode::CallSubroutine(address) => self.call_subroutine(address)?,
             Opcode::Return => self.r#return()?,
             Opcode::SkipInstructionIfEqual(r0, immediate) => {
                 if self.registers[r0 as usize] == immediate {
-                    self.pc += 2
+                    self.skip_instruction();
                 }
             }
             Opcode::SkipInstructionIfNotEqual(r0, immediate) => {
                 if self.registers[r0 as usize] != immediate {
-                    self.pc += 2
+                    self.skip_instruction();
                 }
             }
             Opcode::SkipInstructionIfRegistersEqual(r0, r1) => {
                 if self.registers[r0 as usize] == self.registers[r1 as usize] {
-                    self.pc += 2
+                    self.skip_instruction();
+                }
+            }
+            Opcode::DumpRegisterRangeIntoMemory(r0, r1) => {
+                let address = self.address as usize;
+                self.check_memory(address, r0.abs_diff(r1) as usize + 1)?;
+
+                for (offset, r) in Emulator::register_range(r0, r1).enumerate() {
+                    self.write_memory(address + offset, self.registers[r])?;
+                }
+            }
+            Opcode::DumpMemoryIntoRegisterRange(r0, r1) => {
+                let address = self.address as usize;
+                self.check_memory(address, r0.abs_diff(r1) as usize + 1)?;
+
+                for (offset, r) in Emulator::register_range(r0, r1).enumerate() {
+                    self.registers[r] = self.read_memory(address + offset)?;
                 }
             }
             Opcode::SetRegister(r0, immediate) => {
@@ -236,13 +718,16 @@ impl Emulator {
                 self.registers[r0 as usize] = self.registers[r1 as usize]
             }
             Opcode::OrRegisters(r0, r1) => {
-                self.registers[r0 as usize] |= self.registers[r1 as usize]
+                self.registers[r0 as usize] |= self.registers[r1 as usize];
+                self.reset_flag_register();
             }
             Opcode::AndRegisters(r0, r1) => {
-                self.registers[r0 as usize] &= self.registers[r1 as usize]
+                self.registers[r0 as usize] &= self.registers[r1 as usize];
+                self.reset_flag_register();
             }
             Opcode::XorRegisters(r0, r1) => {
-                self.registers[r0 as usize] ^= self.registers[r1 as usize]
+                self.registers[r0 as usize] ^= self.registers[r1 as usize];
+                self.reset_flag_register();
             }
             Opcode::AddRegisters(r0, r1) => {
                 let result =
@@ -254,11 +739,11 @@ impl Emulator {
                     self.registers[r0 as usize].overflowing_sub(self.registers[r1 as usize]);
                 (self.registers[r0 as usize], self.registers[15]) = (result.0, !result.1 as u8);
             }
-            Opcode::ShiftRegisterRight(r0, _r1) => {
-                let r0 = &mut self.registers[r0 as usize];
-                let bit = *r0 & 0x1;
+            Opcode::ShiftRegisterRight(r0, r1) => {
+                let source = self.shift_source(r0, r1);
+                let bit = source & 0x1;
 
-                *r0 >>= 1;
+                self.registers[r0 as usize] = source >> 1;
                 self.registers[15] = bit;
             }
             Opcode::SubtractRegistersReversed(r0, r1) => {
@@ -266,60 +751,89 @@ impl Emulator {
                     self.registers[r1 as usize].overflowing_sub(self.registers[r0 as usize]);
                 (self.registers[r0 as usize], self.registers[15]) = (result.0, !result.1 as u8);
             }
-            Opcode::ShiftRegisterLeft(r0, _r1) => {
-                let r0 = &mut self.registers[r0 as usize];
-                let bit = *r0 & 0x80;
+            Opcode::ShiftRegisterLeft(r0, r1) => {
+                let source = self.shift_source(r0, r1);
+                let bit = source & 0x80;
 
-                *r0 <<= 1;
+                self.registers[r0 as usize] = source << 1;
                 self.registers[15] = (bit != 0) as u8;
             }
             Opcode::SkipInstructionIfRegistersNotEqual(r0, r1) => {
                 if self.registers[r0 as usize] != self.registers[r1 as usize] {
-                    self.pc += 2;
+                    self.skip_instruction();
                 }
             }
             Opcode::SetMemoryAddress(immediate) => {
                 self.address = immediate;
             }
             Opcode::JumpToMemoryAddress(immediate) => {
-                self.pc = (immediate + self.registers[0] as u16) as usize;
+                let r0 = match self.quirks.jump_with_vx {
+                    true => (immediate >> 8) as usize,
+                    false => 0,
+                };
+                self.goto(immediate + self.registers[r0] as u16);
             }
             Opcode::SetRegisterRandom(r0, immediate) => {
-                let number = random_range(0..=255);
-                self.registers[r0 as usize] = (number & immediate as u32) as u8;
+                let number = self.random_source.next_byte();
+                self.registers[r0 as usize] = number & immediate;
             }
             Opcode::DrawSprite(r0, r1, immediate) => {
-                let (x, y, height) = (
-                    (self.registers[r0 as usize] % DISPLAY_WIDTH as u8) as usize,
-                    (self.registers[r1 as usize] % DISPLAY_HEIGHT as u8) as usize,
-                    immediate as usize,
+                let (display_width, display_height) = (self.display.width(), self.display.height());
+                let (x, y) = (
+                    self.registers[r0 as usize] as usize % display_width,
+                    self.registers[r1 as usize] as usize % display_height,
                 );
 
-                self.registers[15] = 0;
+                // DXY0 draws a 16x16 sprite, but an 8x16 one in low resolution before XO-Chip.
+                let (width, height) = match (immediate, self.display.resolution()) {
+                    (0, Resolution::High) => (16, 16),
+                    (0, Resolution::Low) if self.quirks.lores_big_sprites => (16, 16),
+                    (0, Resolution::Low) => (8, 16),
+                    (n, _) => (8, n as usize),
+                };
+                let bytes_per_row = width / 8;
+                let mut sprite_address = self.address as usize;
+                let sprite_count = (self.planes & ((1 << PLANE_COUNT) - 1)).count_ones() as usize;
+                self.check_memory(sprite_address, sprite_count * height * bytes_per_row)?;
 
-                for dy in 0..height {
-                    let sprite = self.memory[self.address as usize + dy];
-                    for dx in 0..8 {
-                        if y + dy >= DISPLAY_HEIGHT || x + dx >= DISPLAY_WIDTH {
-                            continue;
-                        }
+                self.registers[15] = 0;
 
-                        let sprite_bit = (sprite >> (7 - dx)) & 1;
+                // Each selected plane is drawn from its own sprite, stored one after the other.
+                for plane in (0..PLANE_COUNT).map(|p| 1 << p) {
+                    if self.planes & plane == 0 {
+                        continue;
+                    }
 
-                        if sprite_bit == 1 && self.display[y + dy][x + dx] == 1 {
-                            self.registers[15] = 1;
+                    for dy in 0..height {
+                        for dx in 0..width {
+                            let clipped = y + dy >= display_height || x + dx >= display_width;
+                            if clipped && self.quirks.clip_sprites {
+                                continue;
+                            }
+                            let (px, py) = ((x + dx) % display_width, (y + dy) % display_height);
+
+                            let sprite =
+                                self.read_memory(sprite_address + dy * bytes_per_row + dx / 8)?;
+                            let sprite_bit = (sprite >> (7 - dx % 8)) & 1;
+
+                            if sprite_bit == 1 && self.display.toggle(px, py, plane) {
+                                self.registers[15] = 1;
+                            }
                         }
-
-                        self.display[y + dy][x + dx] ^= sprite_bit;
                     }
+
+                    sprite_address += height * bytes_per_row;
                 }
+
+                // The COSMAC VIP only draws during the vertical blank, one sprite per frame.
+                self.awaiting_vblank = self.quirks.display_wait;
             }
             Opcode::SkipInstructionIfKeyDown(r0) => {
                 let input_address = self.registers[r0 as usize] & 15;
                 let input = self.input[input_address as usize];
 
                 if input != 0 {
-                    self.pc += 2;
+                    self.skip_instruction();
                 }
             }
             Opcode::SkipInstructionIfKeyUp(r0) => {
@@ -327,14 +841,34 @@ impl Emulator {
                 let input = self.input[input_address as usize];
 
                 if input == 0 {
-                    self.pc += 2;
+                    self.skip_instruction();
+                }
+            }
+            Opcode::SetLongMemoryAddress(immediate) => {
+                self.address = immediate;
+            }
+            Opcode::SelectPlanes(immediate) => {
+                self.planes = immediate;
+            }
+            Opcode::LoadAudioPattern => {
+                let address = self.address as usize;
+                let mut pattern = [0; AUDIO_PATTERN_SIZE];
+                self.check_memory(address, AUDIO_PATTERN_SIZE)?;
+
+                for (offset, byte) in pattern.iter_mut().enumerate() {
+                    *byte = self.read_memory(address + offset)?;
                 }
+                self.audio_pattern = Some(pattern);
             }
             Opcode::StoreDelayTimerToRegister(r0) => {
                 self.registers[r0 as usize] = self.delay_timer;
             }
-            Opcode::HaltAndStoreKeypressIntoRegister(_r0) => {
-                self.awaiting_keypress = false;
+            Opcode::HaltAndStoreKeypressIntoRegister(r0) => {
+                // Like the COSMAC VIP, only a key pressed from now on counts, and it's stored on release.
+                self.awaiting_key = Some(KeyWait {
+                    register: r0,
+                    pressed: None,
+                });
             }
             Opcode::SetDelayTimerToRegister(r0) => {
                 self.delay_timer = self.registers[r0 as usize];
@@ -342,6 +876,9 @@ impl Emulator {
             Opcode::SetSoundTimerToRegister(r0) => {
                 self.sound_timer = self.registers[r0 as usize];
             }
+            Opcode::SetPitchToRegister(r0) => {
+                self.pitch = self.registers[r0 as usize];
+            }
             Opcode::AddRegisterToMemoryAddress(r0) => {
                 let result = self
                     .address
@@ -349,27 +886,61 @@ impl Emulator {
                 self.address = result.0;
             }
             Opcode::SetMemoryAddressToSpriteFromRegister(r0) => {
-                let data = self.registers[r0 as usize];
-                self.address = 5 * data as u16;
+                let data = (self.registers[r0 as usize] & 0xF) as usize;
+                self.address = (FONT_DATA_ADDRESS + data * FONT_SPRITE_SIZE) as u16;
+            }
+            Opcode::SetMemoryAddressToLargeSpriteFromRegister(r0) => {
+                let data = (self.registers[r0 as usize] & 0xF) as usize;
+                self.address = (LARGE_FONT_DATA_ADDRESS + data * LARGE_FONT_SPRITE_SIZE) as u16;
+            }
+            Opcode::CallMachineCodeRoutine(address) => {
+                return Err(EmulatorError::UnsupportedMachineCode {
+                    pc: pc as u16,
+                    addr: address,
+                });
             }
-            Opcode::CallMachineCodeRoutine(_) => unimplemented!("This is probably bad memory."),
             Opcode::SetMemoryAddressToBinaryEncodedDecimalFromRegister(r0) => {
                 let data = self.registers[r0 as usize];
-                let (l, m, r) = (data / 100, data % 100 / 10, data % 10);
-
-                self.memory[self.address as usize] = l;
-                self.memory[(self.address + 1) as usize] = m;
-                self.memory[(self.address + 2) as usize] = r;
+                let address = self.address as usize;
+                self.check_memory(address, 3)?;
+
+                for (offset, digit) in [data / 100, data % 100 / 10, data % 10]
+                    .into_iter()
+                    .enumerate()
+                {
+                    self.write_memory(address + offset, digit)?;
+                }
             }
             Opcode::DumpRegistersIntoMemoryUpToRegister(r0) => {
-                for r in 0..r0 {
-                    self.memory[(self.address + r as u16) as usize] = self.registers[r as usize];
+                let address = self.address as usize;
+                self.check_memory(address, r0 as usize + 1)?;
+
+                for r in 0..=r0 as usize {
+                    self.write_memory(address + r, self.registers[r])?;
                 }
+                self.increment_address_after_dump(r0);
             }
             Opcode::DumpMemoryIntoRegistersUpToRegister(r0) => {
-                for r in 0..r0 {
-                    self.registers[r as usize] = self.memory[(self.address + r as u16) as usize];
+                let address = self.address as usize;
+                self.check_memory(address, r0 as usize + 1)?;
+
+                for r in 0..=r0 as usize {
+                    self.registers[r] = self.read_memory(address + r)?;
                 }
+                self.increment_address_after_dump(r0);
+            }
+            Opcode::DumpRegistersIntoFlagsUpToRegister(r0) => {
+                let count = self.flag_count(r0)?;
+                let mut flags = self.flag_storage.load()?;
+
+                flags[..count].copy_from_slice(&self.registers[..count]);
+                self.flag_storage.save(&flags)?;
+            }
+            Opcode::DumpFlagsIntoRegistersUpToRegister(r0) => {
+                let count = self.flag_count(r0)?;
+                let flags = self.flag_storage.load()?;
+
+                self.registers[..count].copy_from_slice(&flags[..count]);
             }
         };
 
@@ -393,16 +964,19 @@ mod tests {
         };
     }
 
+    #[cfg(feature = "std")]
     struct Ts {
         rom_path: &'static str,
     }
 
+    #[cfg(feature = "std")]
     fn init() -> Ts {
         let rom_path = "./src/examples/chip8-roms/games/Pong (1 player).ch8";
 
         Ts { rom_path }
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn load_rom() {
         let Ts { rom_path, .. } = init();
@@ -411,323 +985,1230 @@ mod tests {
         emulator.load_rom(rom_path).unwrap();
     }
 
+    #[test]
+    fn load_embedded_rom() {
+        let mut emulator = Emulator::new();
+
+        emulator
+            .load_bytes(
+                crate::include_rom!("../examples/halt.ch8"),
+                LoadOptions::default(),
+            )
+            .unwrap();
+
+        assert_eq!(emulator.run_frame(), Ok(EmulatorStatus::Done));
+        assert_eq!(emulator.registers()[0xA], 42);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn load_rom_from_reader() {
+        let rom: &[u8] = &[0x6A, 0x2A, 0x12, 0x02];
+        let mut emulator = Emulator::new();
+
+        emulator.load_reader(rom, LoadOptions::ETI_660).unwrap();
+        assert_eq!(emulator.memory()[0x600..0x604], *rom);
+        assert_eq!(emulator.pc(), 0x600);
+
+        assert_eq!(
+            emulator.load_reader(std::io::empty(), LoadOptions::default()),
+            Err(EmulatorError::EmptyRom)
+        );
+        assert_eq!(
+            emulator.load_reader(std::io::repeat(0x12), LoadOptions::default()),
+            Err(EmulatorError::RomTooLarge {
+                size: MEMORY_SIZE + 1,
+                max: MEMORY_SIZE - 0x200
+            })
+        );
+    }
+
+    #[test]
+    fn accessors_expose_the_state() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![
+                Opcode::SetMemoryAddress(0x300),
+                Opcode::SetDelayTimerToRegister(1),
+                Opcode::CallSubroutine(0x208),
+                Opcode::Exit,
+                Opcode::Exit,
+            ])
+            .with_register_as(1, 30)
+            .with_input_as(0xA, 1);
+
+        for _ in 0..3 {
+            assert_update_working!(emulator);
+        }
+
+        assert_eq!(emulator.pc(), 0x208);
+        assert_eq!(emulator.address(), 0x300);
+        assert_eq!(emulator.stack(), [0x206]);
+        assert_eq!(emulator.delay_timer(), 30);
+        assert_eq!(emulator.registers()[1], 30);
+        assert_eq!(emulator.memory()[0x200..0x202], [0xA3, 0x00]);
+        assert!(emulator.is_key_down(0xA));
+        assert!(!emulator.is_key_down(0xB));
+    }
+
     #[test]
     fn clear_screen() {
+        let mut display = Display::new();
+        for y in 0..display.height() {
+            for x in 0..display.width() {
+                display.toggle(x, y, 1);
+            }
+        }
+
         let mut emulator = Emulator::new()
             .with_opcodes(vec![Opcode::ClearScreen])
-            .with_display([[1; 64]; 32]);
+            .with_display(display);
 
         assert_update_working!(emulator);
-        assert_eq!(emulator.display, [[0; 64]; 32]);
+        assert_eq!(emulator.display, Display::new());
     }
 
     #[test]
-    fn opcode_goto() {
-        let mut emulator = Emulator::new().with_opcodes(vec![Opcode::Goto(42)]);
+    fn opcode_enable_high_resolution() {
+        let mut emulator = Emulator::new().with_opcodes(vec![Opcode::EnableHighResolution]);
 
         assert_update_working!(emulator);
-        assert_eq!(emulator.pc, 42);
+        assert_eq!(emulator.display.resolution(), Resolution::High);
+        assert_eq!(
+            (emulator.display.width(), emulator.display.height()),
+            (128, 64)
+        );
     }
 
     #[test]
-    fn opcode_skip_if_register_immediate() {
-        let mut emulator = Emulator::new()
-            .with_opcodes(vec![Opcode::SkipInstructionIfEqual(0, 42)])
-            .with_register_as(0, 42);
+    fn opcode_disable_high_resolution() {
+        let mut emulator = Emulator::new().with_opcodes(vec![
+            Opcode::EnableHighResolution,
+            Opcode::DisableHighResolution,
+        ]);
 
         assert_update_working!(emulator);
-        assert_eq!(emulator.pc, 4 + 0x200)
+        assert_update_working!(emulator);
+        assert_eq!(emulator.display.resolution(), Resolution::Low);
     }
 
     #[test]
-    fn opcode_skip_if_register_immediate_negative() {
+    fn opcode_scroll_down() {
+        let mut display = Display::new();
+        display.set_resolution(Resolution::High);
+        display.toggle(10, 10, 1);
+
         let mut emulator = Emulator::new()
-            .with_opcodes(vec![Opcode::SkipInstructionIfEqual(0, 42)])
-            .with_register_as(0, 69);
+            .with_opcodes(vec![Opcode::ScrollDown(5)])
+            .with_display(display);
 
         assert_update_working!(emulator);
-        assert_eq!(emulator.pc, 2 + 0x200);
+        assert_eq!(emulator.display.get(10, 10), 0);
+        assert_eq!(emulator.display.get(10, 15), 1);
     }
 
     #[test]
-    fn opcode_skip_if_register_not_immediate() {
+    fn opcode_scroll_down_in_low_resolution() {
+        let mut display = Display::new();
+        display.toggle(10, 10, 1);
+
         let mut emulator = Emulator::new()
-            .with_opcodes(vec![Opcode::SkipInstructionIfEqual(0, 42)])
-            .with_register_as(0, 42);
+            .with_opcodes(vec![Opcode::ScrollDown(4)])
+            .with_display(display);
 
         assert_update_working!(emulator);
-        assert_eq!(emulator.pc, 4 + 0x200);
+        assert_eq!(emulator.display.get(10, 10), 0);
+        assert_eq!(emulator.display.get(10, 12), 1);
     }
 
     #[test]
-    fn opcode_skip_if_register_not_immediate_negative() {
+    fn opcode_scroll_right() {
+        let mut display = Display::new();
+        display.set_resolution(Resolution::High);
+        display.toggle(10, 10, 1);
+        display.toggle(127, 10, 1);
+
         let mut emulator = Emulator::new()
-            .with_opcodes(vec![Opcode::SkipInstructionIfNotEqual(0, 42)])
-            .with_register_as(0, 42);
+            .with_opcodes(vec![Opcode::ScrollRight])
+            .with_display(display);
 
         assert_update_working!(emulator);
-        assert_eq!(emulator.pc, 2 + 0x200);
+        assert_eq!(emulator.display.get(10, 10), 0);
+        assert_eq!(emulator.display.get(14, 10), 1);
+        assert_eq!(emulator.display.get(3, 10), 0);
     }
 
     #[test]
-    fn opcode_skip_if_registers_equal() {
+    fn opcode_scroll_right_in_low_resolution() {
+        let mut display = Display::new();
+        display.toggle(10, 10, 1);
+
         let mut emulator = Emulator::new()
-            .with_opcodes(vec![Opcode::SkipInstructionIfRegistersEqual(0, 1)])
-            .with_register_as(0, 42)
-            .with_register_as(1, 42);
+            .with_opcodes(vec![Opcode::ScrollRight])
+            .with_display(display);
 
         assert_update_working!(emulator);
-        assert_eq!(emulator.pc, 4 + 0x200);
+        assert_eq!(emulator.display.get(10, 10), 0);
+        assert_eq!(emulator.display.get(12, 10), 1);
     }
 
     #[test]
-    fn opcode_skip_if_registers_equal_negative() {
+    fn opcode_scroll_left() {
+        let mut display = Display::new();
+        display.set_resolution(Resolution::High);
+        display.toggle(10, 10, 1);
+        display.toggle(0, 10, 1);
+
         let mut emulator = Emulator::new()
-            .with_opcodes(vec![Opcode::SkipInstructionIfRegistersEqual(0, 1)])
-            .with_register_as(0, 42)
-            .with_register_as(1, 69);
+            .with_opcodes(vec![Opcode::ScrollLeft])
+            .with_display(display);
 
         assert_update_working!(emulator);
-        assert_eq!(emulator.pc, 2 + 0x200);
+        assert_eq!(emulator.display.get(10, 10), 0);
+        assert_eq!(emulator.display.get(6, 10), 1);
+        assert_eq!(emulator.display.get(124, 10), 0);
     }
 
     #[test]
-    fn opcode_set_register() {
-        let mut emulator = Emulator::new().with_opcodes(vec![Opcode::SetRegister(0, 42)]);
+    fn opcode_scroll_left_in_low_resolution() {
+        let mut display = Display::new();
+        display.toggle(10, 10, 1);
+
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::ScrollLeft])
+            .with_display(display);
 
         assert_update_working!(emulator);
-        assert_eq!(emulator.registers[0], 42);
+        assert_eq!(emulator.display.get(10, 10), 0);
+        assert_eq!(emulator.display.get(8, 10), 1);
     }
 
     #[test]
-    fn opcode_add_to_register() {
-        let mut emulator = Emulator::new().with_opcodes(vec![
-            Opcode::SetRegister(0, 254),
-            Opcode::AddToRegister(0, 1),
-            Opcode::AddToRegister(0, 1),
-        ]);
+    fn opcode_scroll_up() {
+        let mut display = Display::new();
+        display.set_resolution(Resolution::High);
+        display.toggle(10, 10, 1);
+
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::ScrollUp(3)])
+            .with_display(display);
 
-        assert_eq!(emulator.registers[0], 0);
-        assert_update_working!(emulator);
-        assert_eq!(emulator.registers[0], 254);
         assert_update_working!(emulator);
-        assert_eq!(emulator.registers[0], 255);
+        assert_eq!(emulator.display.get(10, 10), 0);
+        assert_eq!(emulator.display.get(10, 7), 1);
+    }
+
+    #[test]
+    fn opcode_scroll_up_in_low_resolution() {
+        let mut display = Display::new();
+        display.toggle(10, 10, 1);
+
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::ScrollUp(4)])
+            .with_quirks(Quirks::XO_CHIP)
+            .with_display(display);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.display.get(10, 10), 0);
+        assert_eq!(emulator.display.get(10, 6), 1);
+    }
+
+    #[test]
+    fn opcode_scroll_by_whole_pixels_in_low_resolution_on_xo_chip() {
+        let mut display = Display::new();
+        display.toggle(10, 10, 1);
+
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::ScrollDown(2), Opcode::ScrollRight])
+            .with_quirks(Quirks::XO_CHIP)
+            .with_display(display);
+
+        assert_update_working!(emulator);
+        assert_update_working!(emulator);
+        assert_eq!(emulator.display.get(10, 10), 0);
+        assert_eq!(emulator.display.get(14, 12), 1);
+    }
+
+    #[test]
+    fn calling_too_deep_overflows_the_stack() {
+        let mut emulator = Emulator::new().with_opcodes(vec![Opcode::CallSubroutine(0x200)]);
+
+        for _ in 0..STACK_SIZE {
+            assert_update_working!(emulator);
+        }
+
+        assert_eq!(emulator.update(), Err(EmulatorError::StackOverflow));
+    }
+
+    #[test]
+    fn returning_outside_of_a_subroutine_underflows_the_stack() {
+        let mut emulator = Emulator::new().with_opcodes(vec![Opcode::Return]);
+
+        assert_eq!(emulator.update(), Err(EmulatorError::StackUnderflow));
+    }
+
+    #[test]
+    fn unknown_opcodes_report_where_they_are() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::ClearScreen])
+            .with_memory_as(vec![0xF1, 0xFF], 0x202);
+
+        assert_update_working!(emulator);
+        assert_eq!(
+            emulator.update(),
+            Err(EmulatorError::UnknownOpcode {
+                pc: 0x202,
+                bits: 0xF1FF
+            })
+        );
+    }
+
+    #[test]
+    fn running_into_zeroed_memory_fails() {
+        let mut emulator = Emulator::new().with_opcodes(vec![Opcode::ClearScreen]);
+
         assert_update_working!(emulator);
+        assert_eq!(
+            emulator.update(),
+            Err(EmulatorError::UnsupportedMachineCode {
+                pc: 0x202,
+                addr: 0x000
+            })
+        );
+    }
+
+    #[test]
+    fn roms_must_fit_in_memory() {
+        let mut emulator = Emulator::new();
+
+        assert_eq!(
+            emulator.load_bytes(&[0; MEMORY_SIZE], LoadOptions::default()),
+            Err(EmulatorError::RomTooLarge {
+                size: MEMORY_SIZE,
+                max: MEMORY_SIZE - 0x200
+            })
+        );
+        assert_eq!(
+            emulator.load_bytes(&[0; MEMORY_SIZE - 0x200], LoadOptions::default()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn roms_must_fit_in_the_memory_of_the_quirks() {
+        let mut emulator = Emulator::new().with_quirks(Quirks::COSMAC_VIP);
+
+        assert_eq!(
+            emulator.load_bytes(&[0; 0xE01], LoadOptions::default()),
+            Err(EmulatorError::RomTooLarge {
+                size: 0xE01,
+                max: 0xE00
+            })
+        );
+        assert_eq!(
+            emulator.load_bytes(&[0; 0xA01], LoadOptions::ETI_660),
+            Err(EmulatorError::RomTooLarge {
+                size: 0xA01,
+                max: 0xA00
+            })
+        );
+        assert_eq!(
+            emulator.load_bytes(&[0; 0xE00], LoadOptions::default()),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn empty_programs_can_be_built() {
+        let emulator = Emulator::new().with_opcodes(vec![]);
+        assert_eq!(emulator.pc(), 0x200);
+
+        let emulator = Emulator::from(vec![]);
+        assert_eq!(emulator.pc(), 0x200);
+    }
+
+    #[test]
+    fn empty_roms_are_rejected() {
+        let mut emulator = Emulator::new();
+
+        assert_eq!(
+            emulator.load_bytes(&[], LoadOptions::default()),
+            Err(EmulatorError::EmptyRom)
+        );
+    }
+
+    #[test]
+    fn roms_run_from_their_entry_point() {
+        let program = vec![Opcode::SetRegister(0, 1), Opcode::SetRegister(1, 2)].to_bits();
+        let mut emulator = Emulator::new();
+
+        emulator.load_bytes(&program, LoadOptions::ETI_660).unwrap();
+        assert_eq!(emulator.pc(), 0x600);
+        assert_eq!(emulator.memory()[0x600..0x604], program[..]);
+
+        let options = LoadOptions {
+            load_address: 0x300,
+            entry_point: Some(0x302),
+        };
+        emulator.load_bytes(&program, options).unwrap();
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers()[..2], [0, 2]);
+
+        let options = LoadOptions {
+            load_address: 0x300,
+            entry_point: Some(0x1000),
+        };
+        let mut emulator = Emulator::new().with_quirks(Quirks::SCHIP);
+        assert_eq!(
+            emulator.load_bytes(&program, options),
+            Err(EmulatorError::MemoryOutOfBounds { addr: 0x1000 })
+        );
+    }
+
+    #[test]
+    fn memory_faults_are_errors_by_default() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::DrawSprite(0, 0, 5)])
+            .with_quirks(Quirks::COSMAC_VIP)
+            .with_address_as(0xFFF);
+
+        assert_eq!(
+            emulator.update(),
+            Err(EmulatorError::MemoryOutOfBounds { addr: 0x1003 })
+        );
+        assert_eq!(emulator.display, Display::new());
+    }
+
+    #[test]
+    fn fetching_past_the_end_of_memory_faults() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::Goto(0xFFF)])
+            .with_quirks(Quirks::SCHIP);
+
+        assert_update_working!(emulator);
+        assert_eq!(
+            emulator.update(),
+            Err(EmulatorError::MemoryOutOfBounds { addr: 0x1000 })
+        );
+    }
+
+    #[test]
+    fn memory_wraps_around_with_the_wrap_policy() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::DumpRegistersIntoMemoryUpToRegister(2)])
+            .with_quirks(Quirks::SCHIP)
+            .with_memory_policy(MemoryPolicy::Wrap)
+            .with_address_as(0xFFF)
+            .with_register_as(0, 0xBE)
+            .with_register_as(1, 0xEE)
+            .with_register_as(2, 0xEF);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.memory[0xFFF], 0xBE);
+        assert_eq!(emulator.memory[0x000..0x002], [0xEE, 0xEF]);
+        assert_eq!(emulator.memory[0x1000], 0);
+    }
+
+    #[test]
+    fn program_counter_wraps_around_with_the_wrap_policy() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::Goto(0xFFE)])
+            .with_quirks(Quirks::SCHIP)
+            .with_memory_policy(MemoryPolicy::Wrap)
+            .with_memory_as(vec![0x00, 0xE0], 0xFFE);
+
+        assert_update_working!(emulator);
+        assert_update_working!(emulator);
+        assert_eq!(emulator.pc(), 0x000);
+
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::Goto(0xFFE)])
+            .with_quirks(Quirks::SCHIP)
+            .with_memory_policy(MemoryPolicy::Wrap)
+            .with_memory_as(vec![0x23, 0x00], 0xFFE);
+
+        assert_update_working!(emulator);
+        assert_update_working!(emulator);
+        assert_eq!(emulator.stack(), [0x000]);
+    }
+
+    #[test]
+    fn halting_at_the_end_of_memory_is_done_with_the_wrap_policy() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::Goto(0xFFE)])
+            .with_quirks(Quirks::SCHIP)
+            .with_memory_policy(MemoryPolicy::Wrap)
+            .with_memory_as(vec![0x1F, 0xFE], 0xFFE);
+
+        assert_update_working!(emulator);
+        assert_update_done!(emulator);
+        assert_eq!(emulator.pc(), 0xFFE);
+    }
+
+    #[test]
+    fn memory_faults_trap_before_the_instruction() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![
+                Opcode::SetRegister(0, 0xFF),
+                Opcode::DumpMemoryIntoRegistersUpToRegister(3),
+            ])
+            .with_memory_policy(MemoryPolicy::Trap)
+            .with_address_as(0xFFFE);
+
+        assert_update_working!(emulator);
+        assert_eq!(
+            emulator.update(),
+            Ok(EmulatorStatus::Trapped { addr: 0x10001 })
+        );
+        assert_eq!(emulator.pc(), 0x202);
+        assert_eq!(emulator.registers[0], 0xFF);
+
+        emulator = emulator.with_address_as(0x400);
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers[0], 0);
+    }
+
+    #[test]
+    fn opcode_goto() {
+        let mut emulator = Emulator::new().with_opcodes(vec![Opcode::Goto(42)]);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.pc, 42);
+    }
+
+    #[test]
+    fn opcode_goto_self_is_done() {
+        let mut emulator = Emulator::new().with_opcodes(vec![Opcode::Goto(0x200)]);
+
+        assert_update_done!(emulator);
+        assert_eq!(emulator.pc, 0x200);
+        assert_update_done!(emulator);
+    }
+
+    #[test]
+    fn opcode_exit() {
+        let mut emulator =
+            Emulator::new().with_opcodes(vec![Opcode::Exit, Opcode::SetRegister(0, 42)]);
+
+        assert_update_done!(emulator);
+        assert_update_done!(emulator);
+        assert_eq!(emulator.registers[0], 0);
+    }
+
+    #[test]
+    fn opcode_skip_if_register_immediate() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::SkipInstructionIfEqual(0, 42)])
+            .with_register_as(0, 42);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.pc, 4 + 0x200)
+    }
+
+    #[test]
+    fn opcode_skip_if_register_immediate_negative() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::SkipInstructionIfEqual(0, 42)])
+            .with_register_as(0, 69);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.pc, 2 + 0x200);
+    }
+
+    #[test]
+    fn opcode_skip_if_register_not_immediate() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::SkipInstructionIfEqual(0, 42)])
+            .with_register_as(0, 42);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.pc, 4 + 0x200);
+    }
+
+    #[test]
+    fn opcode_skip_if_register_not_immediate_negative() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::SkipInstructionIfNotEqual(0, 42)])
+            .with_register_as(0, 42);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.pc, 2 + 0x200);
+    }
+
+    #[test]
+    fn opcode_skip_if_registers_equal() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::SkipInstructionIfRegistersEqual(0, 1)])
+            .with_register_as(0, 42)
+            .with_register_as(1, 42);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.pc, 4 + 0x200);
+    }
+
+    #[test]
+    fn opcode_skip_if_registers_equal_negative() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::SkipInstructionIfRegistersEqual(0, 1)])
+            .with_register_as(0, 42)
+            .with_register_as(1, 69);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.pc, 2 + 0x200);
+    }
+
+    #[test]
+    fn opcode_skip_over_long_instruction() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![
+                Opcode::SkipInstructionIfEqual(0, 0),
+                Opcode::SetLongMemoryAddress(0xBEEF),
+                Opcode::SetRegister(1, 42),
+            ])
+            .with_address_as(0x400);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.pc, 6 + 0x200);
+        assert_update_working!(emulator);
+        assert_eq!(emulator.address, 0x400);
+        assert_eq!(emulator.registers[1], 42);
+    }
+
+    #[test]
+    fn opcode_dump_register_range_into_memory() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![
+                Opcode::DumpRegisterRangeIntoMemory(1, 3),
+                Opcode::SetMemoryAddress(0x500),
+                Opcode::DumpRegisterRangeIntoMemory(3, 1),
+            ])
+            .with_address_as(0x400)
+            .with_register_as(1, 0xBE)
+            .with_register_as(2, 0xEE)
+            .with_register_as(3, 0xEF);
+
+        for _ in 0..3 {
+            assert_update_working!(emulator);
+        }
+
+        assert_eq!(emulator.memory[0x400..0x404], [0xBE, 0xEE, 0xEF, 0x00]);
+        assert_eq!(emulator.memory[0x500..0x503], [0xEF, 0xEE, 0xBE]);
+        assert_eq!(emulator.address, 0x500);
+    }
+
+    #[test]
+    fn opcode_dump_memory_into_register_range() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::DumpMemoryIntoRegisterRange(4, 2)])
+            .with_address_as(0x400)
+            .with_memory_as(vec![0xDE, 0xEE, 0xED], 0x400);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers[1..6], [0x00, 0xED, 0xEE, 0xDE, 0x00]);
+        assert_eq!(emulator.address, 0x400);
+    }
+
+    #[test]
+    fn opcode_set_register() {
+        let mut emulator = Emulator::new().with_opcodes(vec![Opcode::SetRegister(0, 42)]);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers[0], 42);
+    }
+
+    #[test]
+    fn opcode_add_to_register() {
+        let mut emulator = Emulator::new().with_opcodes(vec![
+            Opcode::SetRegister(0, 254),
+            Opcode::AddToRegister(0, 1),
+            Opcode::AddToRegister(0, 1),
+        ]);
+
         assert_eq!(emulator.registers[0], 0);
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers[0], 254);
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers[0], 255);
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers[0], 0);
+    }
+
+    #[test]
+    fn opcode_copy_registers() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::CopyRegisters(0, 1)])
+            .with_register_as(1, 42);
+
+        assert_eq!(emulator.registers[0], 0);
+        assert_eq!(emulator.registers[1], 42);
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers[1], 42);
+        assert_eq!(emulator.registers[1], 42)
+    }
+
+    #[test]
+    fn opcode_or_registers() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::OrRegisters(0, 1)])
+            .with_register_as(0, 1)
+            .with_register_as(1, 2);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers[0], 3);
+    }
+
+    #[test]
+    fn opcode_and_registers() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::AndRegisters(0, 1)])
+            .with_register_as(0, 1)
+            .with_register_as(1, 2);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers[0], 0);
+    }
+
+    #[test]
+    fn opcode_xor_registers() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::XorRegisters(0, 1)])
+            .with_register_as(0, 1)
+            .with_register_as(1, 1);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers[0], 0);
+    }
+
+    #[test]
+    fn opcode_add_registers() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::AddRegisters(0, 1)])
+            .with_register_as(0, 40)
+            .with_register_as(1, 2);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers[0], 42);
+        assert_eq!(emulator.registers[15], 0);
+    }
+
+    #[test]
+    fn opcode_add_registers_with_overflow() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::AddRegisters(0, 1)])
+            .with_register_as(0, 255)
+            .with_register_as(1, 43);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers[0], 42);
+        assert_eq!(emulator.registers[15], 1);
+    }
+
+    #[test]
+    fn opcode_subtract_registers() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::SubtractRegisters(0, 1)])
+            .with_register_as(0, 255)
+            .with_register_as(1, 213);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers[0], 42);
+        assert_eq!(emulator.registers[15], 1);
+    }
+
+    #[test]
+    fn opcode_subtract_registers_with_underflow() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::SubtractRegisters(0, 1)])
+            .with_register_as(0, 0)
+            .with_register_as(1, 214);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers[0], 42);
+        assert_eq!(emulator.registers[15], 0);
+    }
+
+    #[test]
+    fn opcode_shift_register_right() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::ShiftRegisterRight(0, 1)])
+            .with_register_as(0, 85);
+
+        assert_eq!(emulator.registers[0], 85);
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers[0], 42);
+        assert_eq!(emulator.registers[15], 1);
+    }
+
+    #[test]
+    fn opcode_shift_register_right_from_vy() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::ShiftRegisterRight(0, 1)])
+            .with_quirks(Quirks::COSMAC_VIP)
+            .with_register_as(0, 0xFF)
+            .with_register_as(1, 84);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers[0], 42);
+        assert_eq!(emulator.registers[1], 84);
+        assert_eq!(emulator.registers[15], 0);
+    }
+
+    #[test]
+    fn opcode_subtract_registers_reversed() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::SubtractRegistersReversed(0, 1)])
+            .with_register_as(0, 42)
+            .with_register_as(1, 84);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers[0], 42);
+        assert_eq!(emulator.registers[15], 1);
+    }
+
+    #[test]
+    fn opcode_subtract_registers_reversed_with_underflow() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::SubtractRegistersReversed(0, 1)])
+            .with_register_as(0, 214)
+            .with_register_as(1, 0);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers[0], 42);
+        assert_eq!(emulator.registers[15], 0);
+    }
+
+    #[test]
+    fn opcode_shift_register_left() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::ShiftRegisterLeft(0, 1)])
+            .with_register_as(0, 0b10010101);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers[0], 42);
+        assert_eq!(emulator.registers[15], 1);
+    }
+
+    #[test]
+    fn opcode_shift_register_left_from_vy() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::ShiftRegisterLeft(0, 1)])
+            .with_quirks(Quirks::XO_CHIP)
+            .with_register_as(1, 0b10010101);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers[0], 42);
+        assert_eq!(emulator.registers[15], 1);
+    }
+
+    #[test]
+    fn opcode_logic_resets_flag_register() {
+        for opcode in [
+            Opcode::OrRegisters(0, 1),
+            Opcode::AndRegisters(0, 1),
+            Opcode::XorRegisters(0, 1),
+        ] {
+            let mut emulator = Emulator::new()
+                .with_opcodes(vec![opcode])
+                .with_quirks(Quirks::COSMAC_VIP)
+                .with_register_as(15, 1);
+
+            assert_update_working!(emulator);
+            assert_eq!(emulator.registers[15], 0);
+        }
+    }
+
+    #[test]
+    fn opcode_skip_if_registers_not_equal() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::SkipInstructionIfRegistersNotEqual(0, 1)])
+            .with_register_as(0, 42)
+            .with_register_as(1, 0);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.pc, 4 + 0x200);
+    }
+
+    #[test]
+    fn opcode_skip_if_registers_not_equal_negative() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::SkipInstructionIfRegistersNotEqual(0, 1)])
+            .with_register_as(0, 42)
+            .with_register_as(1, 42);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.pc, 2 + 0x200);
+    }
+
+    #[test]
+    fn opcode_set_memory_address() {
+        let mut emulator = Emulator::new().with_opcodes(vec![Opcode::SetMemoryAddress(0xfef)]);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.address, 0xfef);
+    }
+
+    #[test]
+    fn opcode_set_long_memory_address() {
+        let mut emulator = Emulator::new().with_opcodes(vec![Opcode::SetLongMemoryAddress(0xBEEF)]);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.address, 0xBEEF);
+        assert_eq!(emulator.pc, 4 + 0x200);
+    }
+
+    #[test]
+    fn opcode_select_planes() {
+        let mut emulator = Emulator::new().with_opcodes(vec![Opcode::SelectPlanes(3)]);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.planes, 3);
+    }
+
+    #[test]
+    fn opcode_load_audio_pattern() {
+        let pattern: Vec<u8> = (0..16).collect();
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::LoadAudioPattern])
+            .with_address_as(0x400)
+            .with_memory_as(pattern.clone(), 0x400);
+
+        assert_eq!(emulator.audio_pattern(), None);
+        assert_update_working!(emulator);
+        assert_eq!(emulator.audio_pattern().unwrap()[..], pattern[..]);
+    }
+
+    #[test]
+    fn opcode_set_pitch_to_register() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::SetPitchToRegister(3)])
+            .with_register_as(3, 112);
+
+        assert_eq!(emulator.pitch(), DEFAULT_PITCH);
+        assert_update_working!(emulator);
+        assert_eq!(emulator.pitch(), 112);
+    }
+
+    #[test]
+    fn opcode_jump_to_memory_address() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::JumpToMemoryAddress(0xfef)])
+            .with_register_as(0, 42);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.pc, 0xfef + 42);
     }
 
     #[test]
-    fn opcode_copy_registers() {
+    fn opcode_jump_to_memory_address_with_vx() {
         let mut emulator = Emulator::new()
-            .with_opcodes(vec![Opcode::CopyRegisters(0, 1)])
-            .with_register_as(1, 42);
+            .with_opcodes(vec![Opcode::JumpToMemoryAddress(0x3fe)])
+            .with_quirks(Quirks::SCHIP)
+            .with_register_as(0, 1)
+            .with_register_as(3, 42);
 
-        assert_eq!(emulator.registers[0], 0);
-        assert_eq!(emulator.registers[1], 42);
         assert_update_working!(emulator);
-        assert_eq!(emulator.registers[1], 42);
-        assert_eq!(emulator.registers[1], 42)
+        assert_eq!(emulator.pc, 0x3fe + 42);
     }
 
     #[test]
-    fn opcode_or_registers() {
-        let mut emulator = Emulator::new()
-            .with_opcodes(vec![Opcode::OrRegisters(0, 1)])
-            .with_register_as(0, 1)
-            .with_register_as(1, 2);
+    fn opcode_set_register_random() {
+        let mut values = Vec::new();
 
-        assert_update_working!(emulator);
-        assert_eq!(emulator.registers[0], 3);
+        for seed in 0..10000 {
+            let mut emulator = Emulator::new()
+                .with_opcodes(vec![Opcode::SetRegisterRandom(0, 0xFF)])
+                .with_seed(seed);
+
+            assert_update_working!(emulator);
+            values.push(emulator.registers[0]);
+        }
+
+        let mut average: u64 = 0u64;
+
+        for v in &values {
+            average += *v as u64;
+        }
+
+        average /= values.len() as u64;
+
+        assert!((125..=130).contains(&average));
     }
 
     #[test]
-    fn opcode_and_registers() {
-        let mut emulator = Emulator::new()
-            .with_opcodes(vec![Opcode::AndRegisters(0, 1)])
-            .with_register_as(0, 1)
-            .with_register_as(1, 2);
+    fn opcode_set_register_random_is_reproducible_with_seed() {
+        let run = |seed| {
+            let mut emulator = Emulator::new()
+                .with_opcodes(vec![Opcode::SetRegisterRandom(0, 0xFF); 8])
+                .with_seed(seed);
+
+            (0..8)
+                .map(|_| {
+                    emulator.update().unwrap();
+                    emulator.registers[0]
+                })
+                .collect::<Vec<_>>()
+        };
 
-        assert_update_working!(emulator);
-        assert_eq!(emulator.registers[0], 0);
+        assert_eq!(run(42), run(42));
+        assert_ne!(run(42), run(43));
     }
 
     #[test]
-    fn opcode_xor_registers() {
+    fn opcode_set_register_random_uses_random_source() {
+        #[derive(Debug)]
+        struct Fixed(u8);
+
+        impl RandomSource for Fixed {
+            fn next_byte(&mut self) -> u8 {
+                self.0
+            }
+        }
+
         let mut emulator = Emulator::new()
-            .with_opcodes(vec![Opcode::XorRegisters(0, 1)])
-            .with_register_as(0, 1)
-            .with_register_as(1, 1);
+            .with_opcodes(vec![Opcode::SetRegisterRandom(0, 0x0F)])
+            .with_random_source(Fixed(0xAB));
 
         assert_update_working!(emulator);
-        assert_eq!(emulator.registers[0], 0);
+        assert_eq!(emulator.registers[0], 0x0B);
     }
 
     #[test]
-    fn opcode_add_registers() {
+    fn opcode_draw_sprite() {
         let mut emulator = Emulator::new()
-            .with_opcodes(vec![Opcode::AddRegisters(0, 1)])
-            .with_register_as(0, 40)
-            .with_register_as(1, 2);
+            .with_opcodes(vec![Opcode::SkipInstructionIfKeyDown(0)])
+            .with_register_as(0, 0x1F)
+            .with_input_as(0xF, 1);
 
         assert_update_working!(emulator);
-        assert_eq!(emulator.registers[0], 42);
-        assert_eq!(emulator.registers[15], 0);
     }
 
     #[test]
-    fn opcode_add_registers_with_overflow() {
+    fn opcode_draw_sprite_collision() {
         let mut emulator = Emulator::new()
-            .with_opcodes(vec![Opcode::AddRegisters(0, 1)])
-            .with_register_as(0, 255)
-            .with_register_as(1, 43);
+            .with_opcodes(vec![
+                Opcode::DrawSprite(0, 1, 1),
+                Opcode::DrawSprite(0, 1, 1),
+            ])
+            .with_address_as(0x400)
+            .with_memory_as(vec![0b1010_0000], 0x400)
+            .with_register_as(0, 62)
+            .with_register_as(1, 31);
 
         assert_update_working!(emulator);
-        assert_eq!(emulator.registers[0], 42);
+        assert_eq!(emulator.display.get(62, 31), 1);
+        assert_eq!(emulator.display.get(63, 31), 0);
+        assert_eq!(emulator.registers[15], 0);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.display.get(62, 31), 0);
         assert_eq!(emulator.registers[15], 1);
     }
 
     #[test]
-    fn opcode_subtract_registers() {
+    fn opcode_draw_large_sprite_in_high_resolution() {
         let mut emulator = Emulator::new()
-            .with_opcodes(vec![Opcode::SubtractRegisters(0, 1)])
-            .with_register_as(0, 255)
-            .with_register_as(1, 213);
+            .with_opcodes(vec![
+                Opcode::EnableHighResolution,
+                Opcode::DrawSprite(0, 1, 0),
+            ])
+            .with_address_as(0x400)
+            .with_memory_as(vec![0xFF; 32], 0x400)
+            .with_register_as(0, 100)
+            .with_register_as(1, 40);
 
         assert_update_working!(emulator);
-        assert_eq!(emulator.registers[0], 42);
-        assert_eq!(emulator.registers[15], 1);
+        assert_update_working!(emulator);
+
+        for y in 40..56 {
+            for x in 100..116 {
+                assert_eq!(emulator.display.get(x, y), 1);
+            }
+        }
+        assert_eq!(emulator.display.get(116, 40), 0);
+        assert_eq!(emulator.display.get(100, 56), 0);
     }
 
     #[test]
-    fn opcode_subtract_registers_with_underflow() {
+    fn opcode_draw_large_sprite_in_low_resolution() {
         let mut emulator = Emulator::new()
-            .with_opcodes(vec![Opcode::SubtractRegisters(0, 1)])
-            .with_register_as(0, 0)
-            .with_register_as(1, 214);
+            .with_opcodes(vec![Opcode::DrawSprite(0, 1, 0)])
+            .with_address_as(0x400)
+            .with_memory_as(vec![0xFF; 16], 0x400);
 
         assert_update_working!(emulator);
-        assert_eq!(emulator.registers[0], 42);
-        assert_eq!(emulator.registers[15], 0);
+        assert_eq!(emulator.display.get(7, 15), 1);
+        assert_eq!(emulator.display.get(8, 0), 0);
+        assert_eq!(emulator.display.get(0, 16), 0);
     }
 
     #[test]
-    fn opcode_shift_register_right() {
+    fn opcode_draw_large_sprite_in_low_resolution_on_xo_chip() {
         let mut emulator = Emulator::new()
-            .with_opcodes(vec![Opcode::ShiftRegisterRight(0, 1)])
-            .with_register_as(0, 85);
+            .with_opcodes(vec![Opcode::DrawSprite(0, 1, 0)])
+            .with_quirks(Quirks::XO_CHIP)
+            .with_address_as(0x400)
+            .with_memory_as(vec![0xFF; 32], 0x400);
 
-        assert_eq!(emulator.registers[0], 85);
         assert_update_working!(emulator);
-        assert_eq!(emulator.registers[0], 42);
-        assert_eq!(emulator.registers[15], 1);
+        assert_eq!(emulator.display.get(15, 15), 1);
+        assert_eq!(emulator.display.get(16, 0), 0);
+        assert_eq!(emulator.display.get(0, 16), 0);
     }
 
     #[test]
-    fn opcode_subtract_registers_reversed() {
+    fn opcode_draw_sprite_on_both_planes() {
         let mut emulator = Emulator::new()
-            .with_opcodes(vec![Opcode::SubtractRegistersReversed(0, 1)])
-            .with_register_as(0, 42)
-            .with_register_as(1, 84);
+            .with_opcodes(vec![Opcode::SelectPlanes(3), Opcode::DrawSprite(0, 0, 1)])
+            .with_address_as(0x400)
+            .with_memory_as(vec![0b1100_0000, 0b1010_0000], 0x400);
 
         assert_update_working!(emulator);
-        assert_eq!(emulator.registers[0], 42);
-        assert_eq!(emulator.registers[15], 1);
+        assert_update_working!(emulator);
+
+        assert_eq!(emulator.display.get(0, 0), 0b11);
+        assert_eq!(emulator.display.get(1, 0), 0b01);
+        assert_eq!(emulator.display.get(2, 0), 0b10);
+        assert_eq!(emulator.registers[15], 0);
     }
 
     #[test]
-    fn opcode_subtract_registers_reversed_with_underflow() {
+    fn opcode_draw_sprite_on_second_plane() {
+        let mut display = Display::new();
+        display.toggle(0, 0, 1);
+
         let mut emulator = Emulator::new()
-            .with_opcodes(vec![Opcode::SubtractRegistersReversed(0, 1)])
-            .with_register_as(0, 214)
-            .with_register_as(1, 0);
+            .with_opcodes(vec![Opcode::SelectPlanes(2), Opcode::DrawSprite(0, 0, 1)])
+            .with_address_as(0x400)
+            .with_memory_as(vec![0b1000_0000], 0x400)
+            .with_display(display);
 
         assert_update_working!(emulator);
-        assert_eq!(emulator.registers[0], 42);
+        assert_update_working!(emulator);
+
+        assert_eq!(emulator.display.get(0, 0), 0b11);
         assert_eq!(emulator.registers[15], 0);
     }
 
     #[test]
-    fn opcode_shift_register_left() {
+    fn opcode_clear_screen_on_selected_plane() {
+        let mut display = Display::new();
+        display.toggle(0, 0, 0b11);
+
         let mut emulator = Emulator::new()
-            .with_opcodes(vec![Opcode::ShiftRegisterLeft(0, 1)])
-            .with_register_as(0, 0b10010101);
+            .with_opcodes(vec![Opcode::SelectPlanes(2), Opcode::ClearScreen])
+            .with_display(display);
 
         assert_update_working!(emulator);
-        assert_eq!(emulator.registers[0], 42);
-        assert_eq!(emulator.registers[15], 1);
+        assert_update_working!(emulator);
+
+        assert_eq!(emulator.display.get(0, 0), 0b01);
     }
 
     #[test]
-    fn opcode_skip_if_registers_not_equal() {
+    fn opcode_draw_sprite_wraps_around() {
         let mut emulator = Emulator::new()
-            .with_opcodes(vec![Opcode::SkipInstructionIfRegistersNotEqual(0, 1)])
-            .with_register_as(0, 42)
-            .with_register_as(1, 0);
+            .with_opcodes(vec![Opcode::DrawSprite(0, 1, 2)])
+            .with_quirks(Quirks::XO_CHIP)
+            .with_address_as(0x400)
+            .with_memory_as(vec![0b1100_0000, 0b1100_0000], 0x400)
+            .with_register_as(0, 63)
+            .with_register_as(1, 31);
 
         assert_update_working!(emulator);
-        assert_eq!(emulator.pc, 4 + 0x200);
+        assert_eq!(emulator.display.get(63, 31), 1);
+        assert_eq!(emulator.display.get(0, 31), 1);
+        assert_eq!(emulator.display.get(63, 0), 1);
+        assert_eq!(emulator.display.get(0, 0), 1);
     }
 
     #[test]
-    fn opcode_skip_if_registers_not_equal_negative() {
+    fn opcode_draw_sprite_waits_for_vblank() {
         let mut emulator = Emulator::new()
-            .with_opcodes(vec![Opcode::SkipInstructionIfRegistersNotEqual(0, 1)])
-            .with_register_as(0, 42)
-            .with_register_as(1, 42);
+            .with_opcodes(vec![
+                Opcode::DrawSprite(0, 0, 1),
+                Opcode::DrawSprite(1, 0, 1),
+            ])
+            .with_quirks(Quirks::COSMAC_VIP)
+            .with_instructions_per_frame(2)
+            .with_address_as(0x400)
+            .with_memory_as(vec![0b1000_0000], 0x400)
+            .with_register_as(1, 8);
 
         assert_update_working!(emulator);
-        assert_eq!(emulator.pc, 2 + 0x200);
-    }
+        assert_eq!(emulator.display.get(0, 0), 1);
 
-    #[test]
-    fn opcode_set_memory_address() {
-        let mut emulator = Emulator::new().with_opcodes(vec![Opcode::SetMemoryAddress(0xfef)]);
+        assert_eq!(emulator.update(), Ok(EmulatorStatus::Waiting));
+        assert_eq!(emulator.display.get(8, 0), 0);
 
         assert_update_working!(emulator);
-        assert_eq!(emulator.address, 0xfef);
+        assert_eq!(emulator.display.get(8, 0), 1);
     }
 
     #[test]
-    fn opcode_jump_to_memory_address() {
+    fn update_ticks_timers_every_frame() {
         let mut emulator = Emulator::new()
-            .with_opcodes(vec![Opcode::JumpToMemoryAddress(0xfef)])
-            .with_register_as(0, 42);
+            .with_opcodes(vec![
+                Opcode::SetDelayTimerToRegister(0),
+                Opcode::SetSoundTimerToRegister(0),
+                Opcode::AddToRegister(1, 1),
+                Opcode::AddToRegister(1, 1),
+                Opcode::AddToRegister(1, 1),
+            ])
+            .with_instructions_per_frame(3)
+            .with_register_as(0, 10);
 
         assert_update_working!(emulator);
-        assert_eq!(emulator.pc, 0xfef + 42);
-    }
+        assert_update_working!(emulator);
+        assert_eq!((emulator.delay_timer, emulator.sound_timer), (10, 10));
 
-    #[test]
-    fn opcode_set_register_random() {
-        let mut values = Vec::new();
+        assert_update_working!(emulator);
+        assert_eq!((emulator.delay_timer, emulator.sound_timer), (9, 9));
 
-        for _ in 0..10000 {
-            let mut emulator =
-                Emulator::new().with_opcodes(vec![Opcode::SetRegisterRandom(0, 0xFF)]);
+        assert_update_working!(emulator);
+        assert_update_working!(emulator);
+        assert_eq!((emulator.delay_timer, emulator.sound_timer), (9, 9));
+    }
 
-            assert_update_working!(emulator);
-            values.push(emulator.registers[0]);
-        }
+    #[test]
+    fn run_frame_executes_instructions_then_ticks_timers() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![
+                Opcode::SetRegister(0, 10),
+                Opcode::SetDelayTimerToRegister(0),
+                Opcode::AddToRegister(1, 1),
+                Opcode::AddToRegister(1, 1),
+                Opcode::AddToRegister(1, 1),
+            ])
+            .with_register_as(1, 0)
+            .with_instructions_per_frame(4);
 
-        let mut average: u64 = 0u64;
+        assert_eq!(emulator.run_frame(), Ok(EmulatorStatus::Working));
+        assert_eq!(emulator.registers[1], 2);
+        assert_eq!(emulator.delay_timer, 9);
+    }
 
-        for v in &values {
-            average += *v as u64;
-        }
+    #[test]
+    fn run_frame_ends_early_when_waiting() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![
+                Opcode::DrawSprite(0, 0, 1),
+                Opcode::SetRegister(0, 42),
+                Opcode::SetRegister(1, 42),
+            ])
+            .with_quirks(Quirks::COSMAC_VIP)
+            .with_instructions_per_frame(2);
 
-        average /= values.len() as u64;
+        assert_eq!(emulator.run_frame(), Ok(EmulatorStatus::Waiting));
+        assert_eq!(emulator.registers[0], 0);
 
-        assert!(average >= 125 && average <= 130);
+        assert_eq!(emulator.run_frame(), Ok(EmulatorStatus::Working));
+        assert_eq!(emulator.registers[..2], [42, 42]);
     }
 
     #[test]
-    fn opcode_draw_sprite() {
-        let mut emulator = Emulator::new()
-            .with_opcodes(vec![Opcode::SkipInstructionIfKeyDown(0)])
-            .with_register_as(0, 0x1F)
-            .with_input_as(0xF, 1);
+    fn run_frame_stops_when_done() {
+        let mut emulator = Emulator::new().with_opcodes(vec![Opcode::Exit]);
 
-        assert_update_working!(emulator);
+        assert_eq!(emulator.run_frame(), Ok(EmulatorStatus::Done));
     }
 
     #[test]
@@ -752,6 +2233,103 @@ mod tests {
         assert_eq!(emulator.pc, 2 + 0x200);
     }
 
+    #[test]
+    fn opcode_halt_and_store_keypress_into_register() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![
+                Opcode::HaltAndStoreKeypressIntoRegister(3),
+                Opcode::SetRegister(0, 42),
+            ])
+            .with_instructions_per_frame(1);
+        emulator.press_key(0xB);
+
+        assert_update_working!(emulator);
+        emulator.delay_timer = 10;
+        assert_eq!(emulator.update(), Ok(EmulatorStatus::Waiting));
+        assert_eq!(emulator.delay_timer, 9);
+
+        emulator.release_key(0xB);
+        emulator.press_key(0x7);
+        assert_eq!(emulator.update(), Ok(EmulatorStatus::Waiting));
+
+        emulator.press_key(0x2);
+        emulator.release_key(0x2);
+        assert_eq!(emulator.update(), Ok(EmulatorStatus::Waiting));
+        assert_eq!(emulator.registers[3], 0);
+
+        emulator.release_key(0x7);
+        assert_eq!(emulator.registers[3], 0x7);
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers[0], 42);
+        assert_eq!(emulator.delay_timer, 6);
+    }
+
+    #[test]
+    fn builders_only_consider_the_lowest_nibble() {
+        let emulator = Emulator::new()
+            .with_register_as(0x1A, 42)
+            .with_input_as(0x1F, 1);
+
+        assert_eq!(emulator.registers()[0xA], 42);
+        assert!(emulator.is_key_down(0xF));
+    }
+
+    #[test]
+    fn set_keys_presses_and_releases_changed_keys() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::HaltAndStoreKeypressIntoRegister(0)])
+            .with_input_as(0x1, 1);
+        assert_update_working!(emulator);
+
+        let mut keys = [false; KEY_COUNT];
+        keys[0x1] = true;
+        keys[0xE] = true;
+        emulator.set_keys(keys);
+        assert_eq!(emulator.input[0xE], 1);
+        assert_eq!(emulator.update(), Ok(EmulatorStatus::Waiting));
+
+        keys[0xE] = false;
+        emulator.set_keys(keys);
+        assert_eq!(emulator.input[0x1], 1);
+        assert_eq!(emulator.input[0xE], 0);
+        assert_eq!(emulator.registers[0], 0xE);
+    }
+
+    #[test]
+    fn opcode_set_memory_address_to_sprite_from_register() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::SetMemoryAddressToSpriteFromRegister(0)])
+            .with_register_as(0, 0x1A);
+
+        assert_update_working!(emulator);
+
+        let address = emulator.address as usize;
+        assert_eq!(address, FONT_DATA_ADDRESS + 0xA * FONT_SPRITE_SIZE);
+        assert_eq!(
+            emulator.memory[address..address + FONT_SPRITE_SIZE],
+            [0xF0, 0x90, 0xF0, 0x90, 0x90]
+        );
+    }
+
+    #[test]
+    fn opcode_set_memory_address_to_large_sprite_from_register() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::SetMemoryAddressToLargeSpriteFromRegister(0)])
+            .with_register_as(0, 8);
+
+        assert_update_working!(emulator);
+
+        let address = emulator.address as usize;
+        assert_eq!(
+            address,
+            LARGE_FONT_DATA_ADDRESS + 8 * LARGE_FONT_SPRITE_SIZE
+        );
+        assert_eq!(
+            emulator.memory[address..address + LARGE_FONT_SPRITE_SIZE],
+            [0x3C, 0x7E, 0xC3, 0xC3, 0x7E, 0x7E, 0xC3, 0xC3, 0x7E, 0x3C]
+        );
+    }
+
     #[test]
     fn opcode_set_memory_address_to_binary_encoded_decimal_from_register() {
         let mut emulator = Emulator::new()
@@ -794,4 +2372,101 @@ mod tests {
         assert_eq!(emulator.registers[1], 0xEE);
         assert_eq!(emulator.registers[2], 0xED);
     }
+
+    #[test]
+    fn opcode_dump_registers_into_flags_up_to_register() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![
+                Opcode::DumpRegistersIntoFlagsUpToRegister(2),
+                Opcode::SetRegister(0, 0),
+                Opcode::SetRegister(1, 0),
+                Opcode::SetRegister(2, 0),
+                Opcode::SetRegister(3, 0),
+                Opcode::DumpFlagsIntoRegistersUpToRegister(3),
+            ])
+            .with_register_as(0, 0xDE)
+            .with_register_as(1, 0xAD)
+            .with_register_as(2, 0xBE)
+            .with_register_as(3, 0xEF);
+
+        for _ in 0..6 {
+            assert_update_working!(emulator);
+        }
+
+        assert_eq!(emulator.registers[..4], [0xDE, 0xAD, 0xBE, 0x00]);
+    }
+
+    #[test]
+    fn opcode_dump_flags_into_registers_up_to_register() {
+        let mut storage = MemoryFlagStorage::default();
+        storage
+            .save(&[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16])
+            .unwrap();
+
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::DumpFlagsIntoRegistersUpToRegister(7)])
+            .with_flag_storage(storage)
+            .with_register_as(8, 42);
+
+        assert_update_working!(emulator);
+        assert_eq!(emulator.registers[..9], [1, 2, 3, 4, 5, 6, 7, 8, 42]);
+    }
+
+    #[test]
+    fn opcode_dump_flags_past_the_flag_count_fails() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![Opcode::DumpFlagsIntoRegistersUpToRegister(0xF)])
+            .with_quirks(Quirks::SCHIP)
+            .with_register_as(0xF, 42);
+
+        assert_eq!(
+            emulator.update(),
+            Err(EmulatorError::TooManyFlags { count: 16, max: 8 })
+        );
+        assert_eq!(emulator.registers[0xF], 42);
+    }
+
+    #[test]
+    fn opcode_dump_every_register_into_flags_on_xo_chip() {
+        let mut emulator = Emulator::new()
+            .with_opcodes(vec![
+                Opcode::DumpRegistersIntoFlagsUpToRegister(0xF),
+                Opcode::SetRegister(0xF, 0),
+                Opcode::DumpFlagsIntoRegistersUpToRegister(0xF),
+            ])
+            .with_quirks(Quirks::XO_CHIP)
+            .with_register_as(0x8, 0xBE)
+            .with_register_as(0xF, 0xEF);
+
+        for _ in 0..3 {
+            assert_update_working!(emulator);
+        }
+
+        assert_eq!(emulator.registers[0x8], 0xBE);
+        assert_eq!(emulator.registers[0xF], 0xEF);
+    }
+
+    #[test]
+    fn opcode_dump_registers_increments_address() {
+        for (quirks, address) in [
+            (Quirks::SCHIP, 0x400),
+            (Quirks::CHIP_48, 0x402),
+            (Quirks::COSMAC_VIP, 0x403),
+        ] {
+            let mut emulator = Emulator::new()
+                .with_opcodes(vec![
+                    Opcode::DumpRegistersIntoMemoryUpToRegister(2),
+                    Opcode::SetMemoryAddress(0x400),
+                    Opcode::DumpMemoryIntoRegistersUpToRegister(2),
+                ])
+                .with_quirks(quirks)
+                .with_address_as(0x400);
+
+            assert_update_working!(emulator);
+            assert_eq!(emulator.address, address);
+            assert_update_working!(emulator);
+            assert_update_working!(emulator);
+            assert_eq!(emulator.address, address);
+        }
+    }
 }
@@ -0,0 +1,71 @@
+use core::fmt;
+
+/// Everything that can stop the emulator, or keep a program from being loaded.
+///
+/// Features add variants, e.g. `Io` with `std`, so matches need a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EmulatorError {
+    /// 2NNN was called with every level of the stack in use.
+    StackOverflow,
+    /// 00EE was run outside of a subroutine.
+    StackUnderflow,
+    /// The instruction `bits` at address `pc` isn't one GR8 knows.
+    UnknownOpcode { pc: u16, bits: u16 },
+    /// 0NNN, a call to a machine code routine at `addr`, was run at `pc`. It's usually a program
+    /// running into zeroed memory.
+    UnsupportedMachineCode { pc: u16, addr: u16 },
+    /// An instruction accessed memory past the end.
+    MemoryOutOfBounds { addr: usize },
+    /// A program of `size` bytes doesn't fit in the `max` bytes of memory available to it.
+    RomTooLarge { size: usize, max: usize },
+    /// The ROM has no instructions at all.
+    EmptyRom,
+    /// FX75 or FX85 accessed `count` flags, but the quirks only have `max`.
+    TooManyFlags { count: usize, max: usize },
+    /// Reading a ROM or the flags from a file failed.
+    #[cfg(feature = "std")]
+    Io(std::io::ErrorKind),
+    /// A host-provided flag storage failed.
+    FlagStorage,
+}
+
+impl fmt::Display for EmulatorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EmulatorError::StackOverflow => write!(f, "Stack overflow"),
+            EmulatorError::StackUnderflow => write!(f, "Return outside of a subroutine"),
+            EmulatorError::UnknownOpcode { pc, bits } => {
+                write!(f, "Unknown instruction {bits:04X} at {pc:03X}")
+            }
+            EmulatorError::UnsupportedMachineCode { pc, addr } => {
+                write!(f, "Machine code routine {addr:03X} called at {pc:03X}")
+            }
+            EmulatorError::MemoryOutOfBounds { addr } => {
+                write!(f, "Memory access out of bounds at {addr:X}")
+            }
+            EmulatorError::RomTooLarge { size, max } => {
+                write!(
+                    f,
+                    "The ROM is {size} bytes, but at most {max} fit in memory"
+                )
+            }
+            EmulatorError::EmptyRom => write!(f, "The ROM is empty"),
+            EmulatorError::TooManyFlags { count, max } => {
+                write!(f, "{count} flags accessed, but there are only {max}")
+            }
+            #[cfg(feature = "std")]
+            EmulatorError::Io(kind) => write!(f, "I/O error: {kind}"),
+            EmulatorError::FlagStorage => write!(f, "Couldn't access the flag storage"),
+        }
+    }
+}
+
+impl core::error::Error for EmulatorError {}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for EmulatorError {
+    fn from(error: std::io::Error) -> Self {
+        EmulatorError::Io(error.kind())
+    }
+}
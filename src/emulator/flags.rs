@@ -0,0 +1,185 @@
+use super::error::EmulatorError;
+use core::fmt::Debug;
+#[cfg(feature = "std")]
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+/// The HP-48 exposes eight RPL user flags to Super Chip-8 programs, XO-Chip has one per register.
+pub const FLAG_COUNT: usize = 16;
+
+/// Backing store for the RPL user flags written by FX75 and read by FX85.
+pub trait FlagStorage: Debug {
+    fn load(&mut self) -> Result<[u8; FLAG_COUNT], EmulatorError>;
+    fn save(&mut self, flags: &[u8; FLAG_COUNT]) -> Result<(), EmulatorError>;
+}
+
+/// Keeps the flags for as long as the emulator lives.
+#[derive(Debug, Default)]
+pub struct MemoryFlagStorage {
+    flags: [u8; FLAG_COUNT],
+}
+
+impl FlagStorage for MemoryFlagStorage {
+    fn load(&mut self) -> Result<[u8; FLAG_COUNT], EmulatorError> {
+        Ok(self.flags)
+    }
+
+    fn save(&mut self, flags: &[u8; FLAG_COUNT]) -> Result<(), EmulatorError> {
+        self.flags = *flags;
+        Ok(())
+    }
+}
+
+/// Keeps the flags in memory and persists them to `storage` when it can, e.g. a file in a read-only
+/// directory. Failures are passed to `on_error` instead of stopping the program.
+#[derive(Debug)]
+pub struct BestEffortFlagStorage<S> {
+    storage: S,
+    /// The flags as the program last saved them, once loaded from `storage`.
+    flags: Option<[u8; FLAG_COUNT]>,
+    on_error: fn(EmulatorError),
+}
+
+impl<S: FlagStorage> BestEffortFlagStorage<S> {
+    pub fn new(storage: S, on_error: fn(EmulatorError)) -> Self {
+        BestEffortFlagStorage {
+            storage,
+            flags: None,
+            on_error,
+        }
+    }
+}
+
+impl<S: FlagStorage> FlagStorage for BestEffortFlagStorage<S> {
+    fn load(&mut self) -> Result<[u8; FLAG_COUNT], EmulatorError> {
+        if let Some(flags) = self.flags {
+            return Ok(flags);
+        }
+
+        let flags = self.storage.load().unwrap_or_else(|e| {
+            (self.on_error)(e);
+            [0; FLAG_COUNT]
+        });
+        self.flags = Some(flags);
+
+        Ok(flags)
+    }
+
+    fn save(&mut self, flags: &[u8; FLAG_COUNT]) -> Result<(), EmulatorError> {
+        self.flags = Some(*flags);
+
+        if let Err(e) = self.storage.save(flags) {
+            (self.on_error)(e);
+        }
+        Ok(())
+    }
+}
+
+/// Persists the flags to a file so they survive restarts, e.g. for high scores.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct FileFlagStorage {
+    path: PathBuf,
+}
+
+#[cfg(feature = "std")]
+impl FileFlagStorage {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileFlagStorage { path: path.into() }
+    }
+
+    /// Stores the flags next to the ROM, in a file with the `.flags` extension.
+    pub fn for_rom(path_to_rom: impl AsRef<Path>) -> Self {
+        Self::new(path_to_rom.as_ref().with_extension("flags"))
+    }
+}
+
+#[cfg(feature = "std")]
+impl FlagStorage for FileFlagStorage {
+    fn load(&mut self) -> Result<[u8; FLAG_COUNT], EmulatorError> {
+        let mut flags = [0; FLAG_COUNT];
+
+        match fs::read(&self.path) {
+            Ok(data) => {
+                let length = data.len().min(FLAG_COUNT);
+                flags[..length].copy_from_slice(&data[..length]);
+            }
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        Ok(flags)
+    }
+
+    fn save(&mut self, flags: &[u8; FLAG_COUNT]) -> Result<(), EmulatorError> {
+        Ok(fs::write(&self.path, flags)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use std::env;
+
+    /// Fails like a file in a read-only directory.
+    #[derive(Debug)]
+    struct ReadOnlyStorage;
+
+    impl FlagStorage for ReadOnlyStorage {
+        fn load(&mut self) -> Result<[u8; FLAG_COUNT], EmulatorError> {
+            Ok([7; FLAG_COUNT])
+        }
+
+        fn save(&mut self, _: &[u8; FLAG_COUNT]) -> Result<(), EmulatorError> {
+            Err(EmulatorError::FlagStorage)
+        }
+    }
+
+    #[test]
+    fn best_effort_storage_keeps_the_flags_when_saving_fails() {
+        let mut storage = BestEffortFlagStorage::new(ReadOnlyStorage, |e| {
+            assert_eq!(e, EmulatorError::FlagStorage)
+        });
+        let flags = core::array::from_fn(|i| i as u8);
+
+        assert_eq!(storage.load(), Ok([7; FLAG_COUNT]));
+        assert_eq!(storage.save(&flags), Ok(()));
+        assert_eq!(storage.load(), Ok(flags));
+    }
+
+    #[test]
+    fn memory_storage_round_trips() {
+        let mut storage = MemoryFlagStorage::default();
+
+        let flags = core::array::from_fn(|i| i as u8 + 1);
+
+        storage.save(&flags).unwrap();
+        assert_eq!(storage.load(), Ok(flags));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn file_storage_persists_between_instances() {
+        let path = env::temp_dir().join(format!("gr8-flags-{}.flags", std::process::id()));
+
+        assert_eq!(FileFlagStorage::new(&path).load(), Ok([0; FLAG_COUNT]));
+
+        let flags = core::array::from_fn(|i| (FLAG_COUNT - i) as u8);
+        FileFlagStorage::new(&path).save(&flags).unwrap();
+        assert_eq!(FileFlagStorage::new(&path).load(), Ok(flags));
+
+        fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn file_storage_is_named_after_the_rom() {
+        let storage = FileFlagStorage::for_rom("roms/Ant.ch8");
+
+        assert_eq!(storage.path, PathBuf::from("roms/Ant.flags"));
+    }
+}
@@ -0,0 +1,64 @@
+/// Where most Chip-8 programs are loaded, after the memory reserved for the interpreter.
+pub const DEFAULT_LOAD_ADDRESS: u16 = 0x200;
+
+/// Where a program is copied into memory, and where it starts running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoadOptions {
+    pub load_address: u16,
+    /// The address of the first instruction, the load address when `None`.
+    pub entry_point: Option<u16>,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        LoadOptions {
+            load_address: DEFAULT_LOAD_ADDRESS,
+            entry_point: None,
+        }
+    }
+}
+
+impl LoadOptions {
+    /// Programs for the ETI-660, whose interpreter takes up memory up to 0x600.
+    pub const ETI_660: LoadOptions = LoadOptions {
+        load_address: 0x600,
+        entry_point: None,
+    };
+
+    /// Where the program starts running.
+    pub fn entry_point(&self) -> u16 {
+        self.entry_point.unwrap_or(self.load_address)
+    }
+}
+
+/// Embeds the ROM at `path`, relative to the current file, as a `&'static [u8]` for
+/// [`Emulator::load_bytes`](crate::emulator::Emulator::load_bytes).
+///
+/// Empty ROMs, and ROMs too large for even the 64K of XO-Chip at 0x200, fail to compile. Whether
+/// the ROM fits in the memory size of the quirks, e.g. 4K on the COSMAC VIP, is only checked when
+/// it's loaded.
+///
+/// ```
+/// use gr8::emulator::{Emulator, EmulatorStatus, LoadOptions};
+///
+/// let rom = gr8::include_rom!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/examples/halt.ch8"));
+/// let mut emulator = Emulator::new();
+///
+/// emulator.load_bytes(rom, LoadOptions::default())?;
+/// assert_eq!(emulator.run_frame(), Ok(EmulatorStatus::Done));
+/// # Ok::<(), gr8::emulator::EmulatorError>(())
+/// ```
+#[macro_export]
+macro_rules! include_rom {
+    ($path:expr) => {{
+        const ROM: &[u8] = include_bytes!($path);
+        const _: () = assert!(
+            !ROM.is_empty()
+                && ROM.len()
+                    <= $crate::emulator::MEMORY_SIZE
+                        - $crate::emulator::DEFAULT_LOAD_ADDRESS as usize,
+            "The ROM is empty or larger than any memory"
+        );
+        ROM
+    }};
+}
@@ -1,11 +1,29 @@
-#[derive(Debug, PartialEq)]
+use super::error::EmulatorError;
+use alloc::vec::Vec;
+use core::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
 pub enum Opcode {
     /// 0NNN: Calls machine code routine (RCA 1802 for COSMAC VIP) at address NNN. Not necessary for most ROMs.
     CallMachineCodeRoutine(u16),
+    /// 00CN: Scrolls the display down by N pixels (N/2 pixels in low resolution mode, except on XO-Chip). (Super Chip-8)
+    ScrollDown(u8),
+    /// 00DN: Scrolls the selected planes up by N pixels. (XO-Chip)
+    ScrollUp(u8),
     /// 00E0: Clears the screen.
     ClearScreen,
     /// 00EE: Returns from a subroutine.
     Return,
+    /// 00FB: Scrolls the display right by 4 pixels (2 pixels in low resolution mode, except on XO-Chip). (Super Chip-8)
+    ScrollRight,
+    /// 00FC: Scrolls the display left by 4 pixels (2 pixels in low resolution mode, except on XO-Chip). (Super Chip-8)
+    ScrollLeft,
+    /// 00FD: Exits the interpreter. (Super Chip-8)
+    Exit,
+    /// 00FE: Disables high resolution mode, going back to 64x32 pixels. (Super Chip-8)
+    DisableHighResolution,
+    /// 00FF: Enables high resolution mode, switching to 128x64 pixels. (Super Chip-8)
+    EnableHighResolution,
     /// 1NNN: Jumps to address NNN.
     Goto(u16),
     /// 2NNN: Calls subroutine at NNN.
@@ -16,6 +34,10 @@ pub enum Opcode {
     SkipInstructionIfNotEqual(u8, u8),
     /// 5XY0: Skips the next instruction if VX equals VY (usually the next instruction is a jump to skip a code block).
     SkipInstructionIfRegistersEqual(u8, u8),
+    /// 5XY2: Stores VX to VY (in either direction, including both) in memory, starting at address I. I itself is left unmodified. (XO-Chip)
+    DumpRegisterRangeIntoMemory(u8, u8),
+    /// 5XY3: Fills VX to VY (in either direction, including both) with values from memory, starting at address I. I itself is left unmodified. (XO-Chip)
+    DumpMemoryIntoRegisterRange(u8, u8),
     /// 6XNN: Sets VX to NN.
     SetRegister(u8, u8),
     /// 7XNN: Adds NN to VX (carry flag is not changed).
@@ -49,11 +71,18 @@ pub enum Opcode {
     /// DXYN: Draws a sprite at coordinate (VX, VY) that has a width of 8 pixels and a height of N pixels.
     /// Each row of 8 pixels is read as bit-coded starting from memory location I; I value does not change after the execution of this instruction.
     /// As described above, VF is set to 1 if any screen pixels are flipped from set to unset when the sprite is drawn, and to 0 if that does not happen.
+    /// DXY0 draws a 16x16 sprite (two bytes per row) in high resolution mode and an 8x16 sprite otherwise. (Super Chip-8)
     DrawSprite(u8, u8, u8),
     /// EX9E: Skips the next instruction if the key stored in VX(only consider the lowest nibble) is pressed (usually the next instruction is a jump to skip a code block).
     SkipInstructionIfKeyDown(u8),
     /// EXA1: Skips the next instruction if the key stored in VX(only consider the lowest nibble) is not pressed (usually the next instruction is a jump to skip a code block).
     SkipInstructionIfKeyUp(u8),
+    /// F000 NNNN: Sets I to the 16-bit address NNNN. This is the only 4-byte instruction, `encode` only yields the F000 prefix. (XO-Chip)
+    SetLongMemoryAddress(u16),
+    /// FN01: Selects the drawing planes by the bitmask N. (XO-Chip)
+    SelectPlanes(u8),
+    /// F002: Loads the 16-byte audio pattern starting at address I. (XO-Chip)
+    LoadAudioPattern,
     /// FX07: Sets VX to the value of the delay timer.
     StoreDelayTimerToRegister(u8),
     /// FX0A: A key press is awaited, and then stored in VX (blocking operation, all instruction halted until next key event, delay and sound timers should continue processing).
@@ -62,29 +91,46 @@ pub enum Opcode {
     SetDelayTimerToRegister(u8),
     /// FX18: Sets the sound timer to VX.
     SetSoundTimerToRegister(u8),
+    /// FX3A: Sets the audio pattern playback pitch to VX. (XO-Chip)
+    SetPitchToRegister(u8),
     /// FX1E: Adds VX to I. VF is not affected.
     AddRegisterToMemoryAddress(u8),
     /// FX29: Sets I to the location of the sprite for the character in VX(only consider the lowest nibble). Characters 0-F (in hexadecimal) are represented by a 4x5 font.
     SetMemoryAddressToSpriteFromRegister(u8),
+    /// FX30: Sets I to the location of the large sprite for the character in VX(only consider the lowest nibble). Characters are represented by an 8x10 font. (Super Chip-8)
+    SetMemoryAddressToLargeSpriteFromRegister(u8),
     /// FX33: Stores the binary-coded decimal representation of VX, with the hundreds digit in memory at location in I, the tens digit at location I+1, and the ones digit at location I+2.[
     SetMemoryAddressToBinaryEncodedDecimalFromRegister(u8),
     /// FX55: Stores from V0 to VX (including VX) in memory, starting at address I. The offset from I is increased by 1 for each value written, but I itself is left unmodified.
     DumpRegistersIntoMemoryUpToRegister(u8),
     /// FX65: Fills from V0 to VX (including VX) with values from memory, starting at address I. The offset from I is increased by 1 for each value read, but I itself is left unmodified.
     DumpMemoryIntoRegistersUpToRegister(u8),
+    /// FX75: Stores from V0 to VX (including VX) in the RPL user flags, of which there are 8 (16 on XO-Chip). (Super Chip-8)
+    DumpRegistersIntoFlagsUpToRegister(u8),
+    /// FX85: Fills from V0 to VX (including VX) with values from the RPL user flags, of which there are 8 (16 on XO-Chip). (Super Chip-8)
+    DumpFlagsIntoRegistersUpToRegister(u8),
 }
 
 impl Opcode {
-    pub fn encode(opcode: Opcode) -> Result<(u8, u8), String> {
+    pub fn encode(opcode: Opcode) -> Result<(u8, u8), EmulatorError> {
         let bits = match opcode {
             Opcode::CallMachineCodeRoutine(data) => data.to_bits(),
+            Opcode::ScrollDown(data) => (0x00, 0xC0 | data),
+            Opcode::ScrollUp(data) => (0x00, 0xD0 | data),
             Opcode::ClearScreen => (0x00, 0xE0),
             Opcode::Return => (0x00, 0xEE),
+            Opcode::ScrollRight => (0x00, 0xFB),
+            Opcode::ScrollLeft => (0x00, 0xFC),
+            Opcode::Exit => (0x00, 0xFD),
+            Opcode::DisableHighResolution => (0x00, 0xFE),
+            Opcode::EnableHighResolution => (0x00, 0xFF),
             Opcode::Goto(data) => (0x1000 | data).to_bits(),
             Opcode::CallSubroutine(data) => (0x2000 | data).to_bits(),
             Opcode::SkipInstructionIfEqual(l, r) => (0x30 | l, r),
             Opcode::SkipInstructionIfNotEqual(l, r) => (0x40 | l, r),
             Opcode::SkipInstructionIfRegistersEqual(l, r) => (0x50 | l, r << 4),
+            Opcode::DumpRegisterRangeIntoMemory(l, r) => (0x50 | l, r << 4 | 0x02),
+            Opcode::DumpMemoryIntoRegisterRange(l, r) => (0x50 | l, r << 4 | 0x03),
             Opcode::SetRegister(l, r) => (0x60 | l, r),
             Opcode::AddToRegister(l, r) => (0x70 | l, r),
             Opcode::CopyRegisters(l, r) => (0x80 | l, r),
@@ -103,6 +149,9 @@ impl Opcode {
             Opcode::DrawSprite(l, m, r) => (0xD0 | l, (m << 4) | r),
             Opcode::SkipInstructionIfKeyDown(data) => (0xE09E | (data as u16) << 8).to_bits(),
             Opcode::SkipInstructionIfKeyUp(data) => (0xE0A1 | (data as u16) << 8).to_bits(),
+            Opcode::SetLongMemoryAddress(_) => (0xF0, 0x00),
+            Opcode::SelectPlanes(data) => (0xF0 | data, 0x01),
+            Opcode::LoadAudioPattern => (0xF0, 0x02),
             Opcode::StoreDelayTimerToRegister(data) => (0xF007 | (data as u16) << 8).to_bits(),
             Opcode::HaltAndStoreKeypressIntoRegister(data) => {
                 (0xF00A | (data as u16) << 8).to_bits()
@@ -110,10 +159,14 @@ impl Opcode {
 
             Opcode::SetDelayTimerToRegister(data) => (0xF015 | (data as u16) << 8).to_bits(),
             Opcode::SetSoundTimerToRegister(data) => (0xF018 | (data as u16) << 8).to_bits(),
+            Opcode::SetPitchToRegister(data) => (0xF03A | (data as u16) << 8).to_bits(),
             Opcode::AddRegisterToMemoryAddress(data) => (0xF01E | (data as u16) << 8).to_bits(),
             Opcode::SetMemoryAddressToSpriteFromRegister(data) => {
                 (0xF029 | (data as u16) << 8).to_bits()
             }
+            Opcode::SetMemoryAddressToLargeSpriteFromRegister(data) => {
+                (0xF030 | (data as u16) << 8).to_bits()
+            }
             Opcode::SetMemoryAddressToBinaryEncodedDecimalFromRegister(data) => {
                 (0xF033 | (data as u16) << 8).to_bits()
             }
@@ -124,25 +177,41 @@ impl Opcode {
             Opcode::DumpMemoryIntoRegistersUpToRegister(data) => {
                 (0xF065 | (data as u16) << 8).to_bits()
             }
+            Opcode::DumpRegistersIntoFlagsUpToRegister(data) => {
+                (0xF075 | (data as u16) << 8).to_bits()
+            }
+            Opcode::DumpFlagsIntoRegistersUpToRegister(data) => {
+                (0xF085 | (data as u16) << 8).to_bits()
+            }
         };
 
         Ok(bits)
     }
 
-    pub fn decode(data: (u8, u8)) -> Result<Opcode, String> {
+    /// Decodes a two byte instruction. F000 NNNN is four bytes long, so the emulator decodes it itself.
+    pub fn decode(data: (u8, u8)) -> Result<Opcode, UnknownInstruction> {
         let bits = (data.0 >> 4, data.0 & 0xF, data.1 >> 4, data.1 & 0xF);
 
-        Opcode::decode_bits(bits)
+        Opcode::decode_bits(bits).ok_or(UnknownInstruction {
+            bits: u16::from_be_bytes([data.0, data.1]),
+        })
     }
 
     fn decode_triple_hex_bit(n0: u8, n1: u8, n2: u8) -> u16 {
         ((n0 as u16) << 8) + ((n1 as u16) << 4) + (n2 as u16)
     }
 
-    fn decode_bits(bits: (u8, u8, u8, u8)) -> Result<Opcode, String> {
+    fn decode_bits(bits: (u8, u8, u8, u8)) -> Option<Opcode> {
         let opcode = match bits {
+            (0x0, 0x0, 0xC, n0) => Opcode::ScrollDown(n0),
+            (0x0, 0x0, 0xD, n0) => Opcode::ScrollUp(n0),
             (0x0, 0x0, 0xE, 0x0) => Opcode::ClearScreen,
             (0x0, 0x0, 0xE, 0xE) => Opcode::Return,
+            (0x0, 0x0, 0xF, 0xB) => Opcode::ScrollRight,
+            (0x0, 0x0, 0xF, 0xC) => Opcode::ScrollLeft,
+            (0x0, 0x0, 0xF, 0xD) => Opcode::Exit,
+            (0x0, 0x0, 0xF, 0xE) => Opcode::DisableHighResolution,
+            (0x0, 0x0, 0xF, 0xF) => Opcode::EnableHighResolution,
             (0x0, n0, n1, n2) => {
                 Opcode::CallMachineCodeRoutine(Opcode::decode_triple_hex_bit(n0, n1, n2))
             }
@@ -151,6 +220,8 @@ impl Opcode {
             (0x3, r0, n0, n1) => Opcode::SkipInstructionIfEqual(r0, (n0 << 4) + n1),
             (0x4, r0, n0, n1) => Opcode::SkipInstructionIfNotEqual(r0, (n0 << 4) + n1),
             (0x5, r0, r1, 0) => Opcode::SkipInstructionIfRegistersEqual(r0, r1),
+            (0x5, r0, r1, 2) => Opcode::DumpRegisterRangeIntoMemory(r0, r1),
+            (0x5, r0, r1, 3) => Opcode::DumpMemoryIntoRegisterRange(r0, r1),
             (0x6, r0, n0, n1) => Opcode::SetRegister(r0, (n0 << 4) + n1),
             (0x7, r0, n0, n1) => Opcode::AddToRegister(r0, (n0 << 4) + n1),
             (0x8, r0, r1, 0) => Opcode::CopyRegisters(r0, r1),
@@ -173,20 +244,26 @@ impl Opcode {
             (0xD, r0, r1, n0) => Opcode::DrawSprite(r0, r1, n0),
             (0xE, r0, 0x9, 0xE) => Opcode::SkipInstructionIfKeyDown(r0),
             (0xE, r0, 0xA, 0x1) => Opcode::SkipInstructionIfKeyUp(r0),
+            (0xF, n0, 0x0, 0x1) => Opcode::SelectPlanes(n0),
+            (0xF, 0x0, 0x0, 0x2) => Opcode::LoadAudioPattern,
             (0xF, r0, 0x0, 0x7) => Opcode::StoreDelayTimerToRegister(r0),
             (0xF, r0, 0x0, 0xA) => Opcode::HaltAndStoreKeypressIntoRegister(r0),
             (0xF, r0, 0x1, 0x5) => Opcode::SetDelayTimerToRegister(r0),
             (0xF, r0, 0x1, 0x8) => Opcode::SetSoundTimerToRegister(r0),
             (0xF, r0, 0x1, 0xE) => Opcode::AddRegisterToMemoryAddress(r0),
             (0xF, r0, 0x2, 0x9) => Opcode::SetMemoryAddressToSpriteFromRegister(r0),
+            (0xF, r0, 0x3, 0x0) => Opcode::SetMemoryAddressToLargeSpriteFromRegister(r0),
+            (0xF, r0, 0x3, 0xA) => Opcode::SetPitchToRegister(r0),
             (0xF, r0, 0x3, 0x3) => Opcode::SetMemoryAddressToBinaryEncodedDecimalFromRegister(r0),
             (0xF, r0, 0x5, 0x5) => Opcode::DumpRegistersIntoMemoryUpToRegister(r0),
             (0xF, r0, 0x6, 0x5) => Opcode::DumpMemoryIntoRegistersUpToRegister(r0),
+            (0xF, r0, 0x7, 0x5) => Opcode::DumpRegistersIntoFlagsUpToRegister(r0),
+            (0xF, r0, 0x8, 0x5) => Opcode::DumpFlagsIntoRegistersUpToRegister(r0),
 
-            _ => return Err(format!("Unsupported instruction: {:X?}!", bits)),
+            _ => return None,
         };
 
-        Ok(opcode)
+        Some(opcode)
     }
 }
 
@@ -199,15 +276,41 @@ impl ToBits for Vec<Opcode> {
         let mut bits = Vec::new();
 
         self.into_iter().for_each(|o| {
+            let operand = match o {
+                Opcode::SetLongMemoryAddress(address) => Some(address),
+                _ => None,
+            };
+
             let (l, r) = Opcode::encode(o).unwrap();
             bits.push(l);
             bits.push(r);
+
+            if let Some(address) = operand {
+                let (l, r) = address.to_bits();
+                bits.push(l);
+                bits.push(r);
+            }
         });
 
         bits
     }
 }
 
+/// Two bytes that aren't an instruction of any interpreter GR8 emulates. The emulator reports them
+/// as [`EmulatorError::UnknownOpcode`], along with their address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownInstruction {
+    pub bits: u16,
+}
+
+impl fmt::Display for UnknownInstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown instruction {:04X}", self.bits)
+    }
+}
+
+impl core::error::Error for UnknownInstruction {}
+
 pub trait ToBitsTuple {
     fn to_bits(&self) -> (u8, u8);
 }
@@ -222,11 +325,80 @@ impl ToBitsTuple for u16 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn decoding_an_unknown_instruction_fails() {
+        assert_eq!(
+            Opcode::decode((0xF1, 0xFF)),
+            Err(UnknownInstruction { bits: 0xF1FF })
+        );
+    }
+
     #[test]
     fn can_decode_clear_display_instruction() {
         assert_eq!(Opcode::decode((0x00, 0xE0)), Ok(Opcode::ClearScreen))
     }
 
+    #[test]
+    fn can_decode_resolution_instructions() {
+        assert_eq!(
+            Opcode::decode((0x00, 0xFE)),
+            Ok(Opcode::DisableHighResolution)
+        );
+        assert_eq!(
+            Opcode::decode((0x00, 0xFF)),
+            Ok(Opcode::EnableHighResolution)
+        );
+    }
+
+    #[test]
+    fn can_decode_scroll_instructions() {
+        assert_eq!(Opcode::decode((0x00, 0xC7)), Ok(Opcode::ScrollDown(7)));
+        assert_eq!(Opcode::decode((0x00, 0xFB)), Ok(Opcode::ScrollRight));
+        assert_eq!(Opcode::decode((0x00, 0xFC)), Ok(Opcode::ScrollLeft));
+    }
+
+    #[test]
+    fn can_encode_scroll_instructions() {
+        assert_eq!(Opcode::encode(Opcode::ScrollDown(7)), Ok((0x00, 0xC7)));
+        assert_eq!(Opcode::encode(Opcode::ScrollRight), Ok((0x00, 0xFB)));
+        assert_eq!(Opcode::encode(Opcode::ScrollLeft), Ok((0x00, 0xFC)));
+    }
+
+    #[test]
+    fn can_decode_large_sprite_instruction() {
+        assert_eq!(
+            Opcode::decode((0xF4, 0x30)),
+            Ok(Opcode::SetMemoryAddressToLargeSpriteFromRegister(4))
+        );
+    }
+
+    #[test]
+    fn can_decode_xo_chip_instructions() {
+        assert_eq!(Opcode::decode((0x00, 0xD3)), Ok(Opcode::ScrollUp(3)));
+        assert_eq!(
+            Opcode::decode((0x51, 0x42)),
+            Ok(Opcode::DumpRegisterRangeIntoMemory(1, 4))
+        );
+        assert_eq!(
+            Opcode::decode((0x51, 0x43)),
+            Ok(Opcode::DumpMemoryIntoRegisterRange(1, 4))
+        );
+        assert_eq!(Opcode::decode((0xF2, 0x01)), Ok(Opcode::SelectPlanes(2)));
+        assert_eq!(Opcode::decode((0xF0, 0x02)), Ok(Opcode::LoadAudioPattern));
+        assert_eq!(
+            Opcode::decode((0xF5, 0x3A)),
+            Ok(Opcode::SetPitchToRegister(5))
+        );
+    }
+
+    #[test]
+    fn can_encode_long_memory_address_instruction() {
+        assert_eq!(
+            vec![Opcode::SetLongMemoryAddress(0xBEEF), Opcode::ClearScreen].to_bits(),
+            vec![0xF0, 0x00, 0xBE, 0xEF, 0x00, 0xE0]
+        );
+    }
+
     #[test]
     fn can_decode_add_registers_instruction() {
         assert_eq!(
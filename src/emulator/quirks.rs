@@ -0,0 +1,116 @@
+use super::emulator::MEMORY_SIZE;
+
+/// How far FX55 and FX65 move I after storing or loading registers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MemoryIncrement {
+    /// I is left unmodified.
+    None,
+    /// I is increased by X.
+    ByX,
+    /// I is increased by X + 1, i.e. it ends up just past the last byte accessed.
+    ByXPlusOne,
+}
+
+/// Toggles for the instructions whose behaviour differs between Chip-8 interpreters.
+///
+/// The default matches what GR8 has always done, the presets match the interpreters
+/// most ROMs were written for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quirks {
+    /// 8XY6 and 8XYE shift VY and store the result in VX, instead of shifting VX in place.
+    pub shift_uses_vy: bool,
+    /// BNNN jumps to XNN plus VX, instead of NNN plus V0.
+    pub jump_with_vx: bool,
+    /// How FX55 and FX65 move I.
+    pub memory_increment: MemoryIncrement,
+    /// 8XY1, 8XY2 and 8XY3 reset VF to 0.
+    pub vf_reset: bool,
+    /// Sprites are clipped at the edges of the screen, instead of wrapping around.
+    pub clip_sprites: bool,
+    /// DXYN waits for the next vertical blank, allowing a single sprite per frame.
+    pub display_wait: bool,
+    /// DXY0 draws a 16x16 sprite in low resolution too, instead of an 8x16 one.
+    pub lores_big_sprites: bool,
+    /// 00CN, 00FB and 00FC (and XO-Chip's 00DN) scroll by half as many pixels in low resolution,
+    /// as Super Chip-8 1.1 counts them in high resolution pixels.
+    pub half_pixel_scroll: bool,
+    /// How many registers FX75 and FX85 can save and restore, 8 on the HP-48.
+    pub flag_count: usize,
+    /// How many bytes of memory the program can address, 4K on the original interpreters.
+    pub memory_size: usize,
+}
+
+impl Default for Quirks {
+    fn default() -> Self {
+        Quirks {
+            shift_uses_vy: false,
+            jump_with_vx: false,
+            memory_increment: MemoryIncrement::None,
+            vf_reset: false,
+            clip_sprites: true,
+            display_wait: false,
+            lores_big_sprites: false,
+            half_pixel_scroll: true,
+            flag_count: 8,
+            memory_size: MEMORY_SIZE,
+        }
+    }
+}
+
+impl Quirks {
+    /// The original Chip-8 interpreter on the COSMAC VIP.
+    pub const COSMAC_VIP: Quirks = Quirks {
+        shift_uses_vy: true,
+        jump_with_vx: false,
+        memory_increment: MemoryIncrement::ByXPlusOne,
+        vf_reset: true,
+        clip_sprites: true,
+        display_wait: true,
+        lores_big_sprites: false,
+        half_pixel_scroll: false,
+        flag_count: 8,
+        memory_size: 0x1000,
+    };
+
+    /// CHIP-48 on the HP-48 calculators.
+    pub const CHIP_48: Quirks = Quirks {
+        shift_uses_vy: false,
+        jump_with_vx: true,
+        memory_increment: MemoryIncrement::ByX,
+        vf_reset: false,
+        clip_sprites: true,
+        display_wait: false,
+        lores_big_sprites: false,
+        half_pixel_scroll: false,
+        flag_count: 8,
+        memory_size: 0x1000,
+    };
+
+    /// Super Chip-8 1.1 on the HP-48 calculators.
+    pub const SCHIP: Quirks = Quirks {
+        shift_uses_vy: false,
+        jump_with_vx: true,
+        memory_increment: MemoryIncrement::None,
+        vf_reset: false,
+        clip_sprites: true,
+        display_wait: false,
+        lores_big_sprites: false,
+        half_pixel_scroll: true,
+        flag_count: 8,
+        memory_size: 0x1000,
+    };
+
+    /// XO-Chip, as implemented by Octo.
+    pub const XO_CHIP: Quirks = Quirks {
+        shift_uses_vy: true,
+        jump_with_vx: false,
+        memory_increment: MemoryIncrement::ByXPlusOne,
+        vf_reset: false,
+        clip_sprites: false,
+        display_wait: false,
+        lores_big_sprites: true,
+        half_pixel_scroll: false,
+        flag_count: 16,
+        memory_size: MEMORY_SIZE,
+    };
+}
@@ -0,0 +1,51 @@
+use core::fmt::Debug;
+
+/// Where CXNN gets its random numbers from.
+pub trait RandomSource: Debug {
+    fn next_byte(&mut self) -> u8;
+}
+
+/// SplitMix64: tiny, and the same sequence for the same seed on every platform and build.
+#[derive(Debug, Clone)]
+pub struct SeededRandomSource {
+    state: u64,
+}
+
+impl SeededRandomSource {
+    pub fn new(seed: u64) -> Self {
+        SeededRandomSource { state: seed }
+    }
+}
+
+impl RandomSource for SeededRandomSource {
+    fn next_byte(&mut self) -> u8 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+
+        (z >> 56) as u8
+    }
+}
+
+#[cfg(feature = "rand")]
+impl RandomSource for rand::rngs::SmallRng {
+    fn next_byte(&mut self) -> u8 {
+        rand::Rng::random(self)
+    }
+}
+
+/// A generator seeded by the operating system, different on every run.
+#[cfg(feature = "rand")]
+pub(crate) fn default_random_source() -> impl RandomSource {
+    <rand::rngs::SmallRng as rand::SeedableRng>::from_os_rng()
+}
+
+/// Without an operating system to ask for entropy, every run starts from the same seed, so
+/// hosts should seed the emulator themselves.
+#[cfg(not(feature = "rand"))]
+pub(crate) fn default_random_source() -> impl RandomSource {
+    SeededRandomSource::new(0)
+}
@@ -0,0 +1,223 @@
+use crate::bindings::parse_key;
+use gr8::emulator::KEY_COUNT;
+use toml::Table;
+
+/// The controller buttons that can be bound, named after their position on the pad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Button {
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+    /// The bottom face button, A on Xbox controllers.
+    South,
+    East,
+    North,
+    West,
+    Start,
+    Select,
+}
+
+const BUTTON_NAMES: [(&str, Button); 10] = [
+    ("DPadUp", Button::DPadUp),
+    ("DPadDown", Button::DPadDown),
+    ("DPadLeft", Button::DPadLeft),
+    ("DPadRight", Button::DPadRight),
+    ("South", Button::South),
+    ("East", Button::East),
+    ("North", Button::North),
+    ("West", Button::West),
+    ("Start", Button::Start),
+    ("Select", Button::Select),
+];
+
+/// Most games move with 5, 7, 8 and 9 (or 4 and 6), and act with 6 or A.
+pub const DEFAULT_GAMEPAD_BINDINGS: [(Button, u8); 8] = [
+    (Button::DPadUp, 0x5),
+    (Button::DPadLeft, 0x7),
+    (Button::DPadDown, 0x8),
+    (Button::DPadRight, 0x9),
+    (Button::South, 0x6),
+    (Button::East, 0x4),
+    (Button::West, 0xA),
+    (Button::North, 0xB),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ButtonEvent {
+    Pressed(Button),
+    Released(Button),
+}
+
+/// Tracks the buttons held on any connected controller and which Chip-8 keys they press.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gamepad {
+    bindings: Vec<(Button, u8)>,
+    held: Vec<Button>,
+}
+
+impl Default for Gamepad {
+    fn default() -> Self {
+        Self::new(DEFAULT_GAMEPAD_BINDINGS.to_vec())
+    }
+}
+
+impl Gamepad {
+    pub fn new(bindings: Vec<(Button, u8)>) -> Self {
+        Gamepad {
+            bindings,
+            held: Vec::new(),
+        }
+    }
+
+    /// Reads the bindings from a table mapping button names to hex digits, e.g. `DPadUp = "5"`.
+    pub fn from_table(table: &Table) -> Result<Self, String> {
+        let mut bindings = Vec::new();
+
+        for (name, value) in table {
+            let button = BUTTON_NAMES
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, button)| *button)
+                .ok_or(format!("Unknown gamepad button {name}."))?;
+            bindings.push((button, parse_key(name, value)?));
+        }
+
+        Ok(Self::new(bindings))
+    }
+
+    pub fn handle(&mut self, event: ButtonEvent) {
+        match event {
+            ButtonEvent::Pressed(button) if !self.held.contains(&button) => self.held.push(button),
+            ButtonEvent::Pressed(_) => {}
+            ButtonEvent::Released(button) => self.held.retain(|b| *b != button),
+        }
+    }
+
+    /// Lets go of every button, e.g. when a controller is disconnected.
+    pub fn release_all(&mut self) {
+        self.held.clear();
+    }
+
+    /// The state of the keypad according to the held buttons.
+    pub fn keypad(&self) -> [bool; KEY_COUNT] {
+        let mut keys = [false; KEY_COUNT];
+
+        for &(button, key) in &self.bindings {
+            keys[key as usize] |= self.held.contains(&button);
+        }
+
+        keys
+    }
+}
+
+/// Feeds the button presses of the controllers found by gilrs into a [`Gamepad`].
+#[cfg(feature = "gamepad")]
+pub struct Controllers {
+    gilrs: gilrs::Gilrs,
+}
+
+#[cfg(feature = "gamepad")]
+impl Controllers {
+    pub fn new() -> Result<Self, String> {
+        let gilrs = gilrs::Gilrs::new().map_err(|e| e.to_string())?;
+
+        Ok(Controllers { gilrs })
+    }
+
+    /// Handles every event received since the last call.
+    pub fn poll(&mut self, gamepad: &mut Gamepad) {
+        use gilrs::EventType;
+
+        while let Some(gilrs::Event { event, .. }) = self.gilrs.next_event() {
+            match event {
+                EventType::ButtonPressed(button, _) => {
+                    if let Some(button) = Self::button(button) {
+                        gamepad.handle(ButtonEvent::Pressed(button));
+                    }
+                }
+                EventType::ButtonReleased(button, _) => {
+                    if let Some(button) = Self::button(button) {
+                        gamepad.handle(ButtonEvent::Released(button));
+                    }
+                }
+                EventType::Disconnected => gamepad.release_all(),
+                _ => {}
+            }
+        }
+    }
+
+    fn button(button: gilrs::Button) -> Option<Button> {
+        match button {
+            gilrs::Button::DPadUp => Some(Button::DPadUp),
+            gilrs::Button::DPadDown => Some(Button::DPadDown),
+            gilrs::Button::DPadLeft => Some(Button::DPadLeft),
+            gilrs::Button::DPadRight => Some(Button::DPadRight),
+            gilrs::Button::South => Some(Button::South),
+            gilrs::Button::East => Some(Button::East),
+            gilrs::Button::North => Some(Button::North),
+            gilrs::Button::West => Some(Button::West),
+            gilrs::Button::Start => Some(Button::Start),
+            gilrs::Button::Select => Some(Button::Select),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pressed(keys: &[u8]) -> [bool; KEY_COUNT] {
+        let mut keypad = [false; KEY_COUNT];
+        for key in keys {
+            keypad[*key as usize] = true;
+        }
+        keypad
+    }
+
+    #[test]
+    fn buttons_press_their_keys_until_released() {
+        let mut gamepad = Gamepad::default();
+
+        gamepad.handle(ButtonEvent::Pressed(Button::DPadUp));
+        gamepad.handle(ButtonEvent::Pressed(Button::South));
+        assert_eq!(gamepad.keypad(), pressed(&[0x5, 0x6]));
+
+        gamepad.handle(ButtonEvent::Released(Button::DPadUp));
+        assert_eq!(gamepad.keypad(), pressed(&[0x6]));
+
+        gamepad.handle(ButtonEvent::Pressed(Button::Start));
+        assert_eq!(gamepad.keypad(), pressed(&[0x6]));
+
+        gamepad.release_all();
+        assert_eq!(gamepad.keypad(), pressed(&[]));
+    }
+
+    #[test]
+    fn repeated_presses_need_a_single_release() {
+        let mut gamepad = Gamepad::default();
+
+        gamepad.handle(ButtonEvent::Pressed(Button::DPadLeft));
+        gamepad.handle(ButtonEvent::Pressed(Button::DPadLeft));
+        gamepad.handle(ButtonEvent::Released(Button::DPadLeft));
+
+        assert_eq!(gamepad.keypad(), pressed(&[]));
+    }
+
+    #[test]
+    fn reads_bindings_from_a_table() {
+        let table: Table = "DPadLeft = \"4\"\nDPadRight = \"6\"\nStart = \"f\""
+            .parse()
+            .unwrap();
+        let mut gamepad = Gamepad::from_table(&table).unwrap();
+
+        gamepad.handle(ButtonEvent::Pressed(Button::DPadRight));
+        gamepad.handle(ButtonEvent::Pressed(Button::Start));
+        gamepad.handle(ButtonEvent::Pressed(Button::South));
+        assert_eq!(gamepad.keypad(), pressed(&[0x6, 0xF]));
+
+        let table: Table = "Turbo = \"1\"".parse().unwrap();
+        assert!(Gamepad::from_table(&table).is_err());
+    }
+}
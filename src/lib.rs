@@ -0,0 +1,26 @@
+//! A Chip-8, Super Chip-8 and XO-Chip interpreter.
+//!
+//! The [`emulator::Emulator`] has no dependency on a frontend: feed it keys, call
+//! [`run_frame`](emulator::Emulator::run_frame) 60 times per second and draw its
+//! [`display`](emulator::Emulator::display).
+//!
+//! Without the default `std` feature the library is `no_std`, needing only `alloc`.
+//!
+//! ```
+//! use gr8::emulator::{Emulator, EmulatorStatus, Opcode};
+//!
+//! let mut emulator = Emulator::new().with_opcodes(vec![
+//!     Opcode::SetRegister(0, 42),
+//!     Opcode::Exit,
+//! ]);
+//!
+//! assert_eq!(emulator.run_frame(), Ok(EmulatorStatus::Done));
+//! assert_eq!(emulator.registers()[0], 42);
+//! assert_eq!(emulator.pc(), 0x204);
+//! ```
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+pub mod emulator;
@@ -1,41 +1,218 @@
-mod emulator;
+mod bindings;
+mod cli;
+// Only the `gamepad` feature reads controllers outside of tests.
+#[cfg_attr(not(feature = "gamepad"), allow(dead_code))]
+mod gamepad;
+#[cfg(feature = "audio")]
+mod sound;
 
+use bindings::{BindingsFile, RebindMenu};
+use cli::{Command, Options, USAGE};
+use gr8::emulator::{
+    BestEffortFlagStorage, Emulator, EmulatorStatus, FileFlagStorage, LORES_HEIGHT, LORES_WIDTH,
+    MemoryPolicy, Quirks,
+};
 use macroquad::prelude::*;
-use emulator::Emulator;
-use std::time::SystemTime;
+use std::fmt::Display;
+use std::path::Path;
+use std::{env, fs, process};
 
-#[macroquad::main("GR8")]
-async fn main() {
-    let mut emulator = Emulator::new();
-    emulator.load_rom("src/examples/chip8-roms/games/Pong (1 player).ch8").unwrap();
+/// The emulator runs one frame per tick of the 60Hz timers.
+const FRAME_TIME: f32 = 1.0 / 60.0;
+/// How many frames to catch up on at most when rendering falls behind.
+const MAX_FRAMES_PER_RENDER: u32 = 4;
+/// Closes the window once the program is done.
+const QUIT_KEY: KeyCode = KeyCode::Escape;
+/// Opens the menu to rebind the keypad, which Escape closes again.
+const REBIND_KEY: KeyCode = KeyCode::F1;
+/// Switches the sound off and on again.
+#[cfg(feature = "audio")]
+const MUTE_KEY: KeyCode = KeyCode::F2;
 
-    let time = SystemTime::now();
+/// Picks the quirks and instructions per frame from the conventional ROM extensions, defaulting to the original Chip-8.
+fn profile_for_rom(path_to_rom: &Path) -> (Quirks, u32) {
+    match path_to_rom.extension().and_then(|e| e.to_str()) {
+        Some("sc8") => (Quirks::SCHIP, 30),
+        Some("xo8") => (Quirks::XO_CHIP, 1000),
+        _ => (Quirks::COSMAC_VIP, 15),
+    }
+}
+
+/// Prints the error and exits, for the mistakes that leave nothing to run.
+fn fail(error: impl Display) -> ! {
+    eprintln!("gr8: {error}");
+    process::exit(1);
+}
+
+fn main() {
+    let options = match cli::parse(env::args().skip(1)) {
+        Ok(Command::Run(options)) => options,
+        Ok(Command::Help) => {
+            print!("{USAGE}");
+            return;
+        }
+        Err(e) => {
+            eprintln!("gr8: {e}\n\n{USAGE}");
+            process::exit(2);
+        }
+    };
+
+    let rom = fs::read(&options.rom_path)
+        .unwrap_or_else(|e| fail(format!("Couldn't read {}: {e}", options.rom_path.display())));
+    let (quirks, instructions_per_frame) = profile_for_rom(&options.rom_path);
+    let mut emulator = Emulator::new()
+        .with_quirks(options.quirks.unwrap_or(quirks))
+        .with_instructions_per_frame(options.speed.unwrap_or(instructions_per_frame))
+        // Buggy homebrew wanders out of memory, which wrapped around on the real hardware too.
+        .with_memory_policy(MemoryPolicy::Wrap)
+        // Saving the flags is best effort, e.g. the ROM may sit in a read-only directory.
+        .with_flag_storage(BestEffortFlagStorage::new(
+            FileFlagStorage::for_rom(&options.rom_path),
+            |e| eprintln!("Couldn't access the flags file: {e}"),
+        ));
+    emulator
+        .load_bytes(&rom, options.load_options)
+        .unwrap_or_else(|e| fail(format!("Couldn't load {}: {e}", options.rom_path.display())));
+
+    if options.mute && !cfg!(feature = "audio") {
+        eprintln!("gr8: --mute has no effect without the audio feature.");
+    }
+
+    let bindings_file = BindingsFile::load(&options.keymap).unwrap_or_else(|e| fail(e));
+    let rom_hash = bindings::rom_hash(&rom);
+
+    let conf = Conf {
+        window_title: "GR8".to_string(),
+        window_width: (LORES_WIDTH as u32 * options.scale) as i32,
+        window_height: (LORES_HEIGHT as u32 * options.scale) as i32,
+        ..Default::default()
+    };
+
+    macroquad::Window::from_config(conf, run(options, emulator, bindings_file, rom_hash));
+}
+
+async fn run(
+    options: Options,
+    mut emulator: Emulator,
+    mut bindings_file: BindingsFile,
+    rom_hash: String,
+) {
+    let palette = options.palette;
+    let mut bindings = bindings_file
+        .bindings_for(&rom_hash)
+        .unwrap_or_else(|e| fail(e));
+    let mut rebind_menu: Option<RebindMenu> = None;
+
+    #[cfg(feature = "gamepad")]
+    let mut gamepad = bindings_file.gamepad().unwrap_or_else(|e| fail(e));
+    #[cfg(feature = "gamepad")]
+    let mut controllers = gamepad::Controllers::new()
+        .map_err(|e| eprintln!("Controllers are unavailable: {e}"))
+        .ok();
+
+    let mut pending_time = 0.0;
+    // The last screen of a program stays up until the window is closed.
+    let mut done = false;
+
+    #[cfg(feature = "audio")]
+    let mut speaker = sound::Speaker::new(gr8::emulator::Beeper {
+        muted: options.mute,
+        ..Default::default()
+    });
 
     loop {
-        let width = screen_width() as i32;
-        let height = screen_height() as i32;
-        let dx = width / 64;
-        let dy = height / 32;
-
-        emulator.time_in_ms = time.elapsed().expect("I am genuinely uncertain as to why this would happen.").as_millis();
-
-        emulator.update().expect("Couldn't update");
-
-        for y in 0..32 {
-            for x in 0..64 {
-                let color = match x % 4 {
-                    _ => WHITE
-                };
-
-                if emulator.display[y as usize][x as usize] == 0 { continue; }
-                    
-                draw_rectangle(
-                    (x * dx) as f32, 
-                    (y * dy) as f32, 
-                    dx as f32, 
-                    dy as f32,
-                    color);
+        if let Some(menu) = rebind_menu.as_mut() {
+            match get_last_key_pressed() {
+                Some(KeyCode::Escape) => rebind_menu = None,
+                Some(key_code) => {
+                    if let Some(new_bindings) = menu.bind(key_code) {
+                        bindings_file.set_bindings(&rom_hash, &new_bindings);
+                        if let Err(e) = bindings_file.save() {
+                            eprintln!("Couldn't save the key bindings: {e}");
+                        }
+
+                        bindings = new_bindings;
+                        rebind_menu = None;
+                    }
+                }
+                None => {}
             }
+        } else if is_key_pressed(REBIND_KEY) {
+            rebind_menu = Some(RebindMenu::default());
+        } else if done && is_key_pressed(QUIT_KEY) {
+            return;
+        }
+
+        let keys = bindings.keypad(is_key_down);
+
+        // The controllers press keys on top of the keyboard.
+        #[cfg(feature = "gamepad")]
+        let keys = {
+            if let Some(controllers) = controllers.as_mut() {
+                controllers.poll(&mut gamepad);
+            }
+
+            let buttons = gamepad.keypad();
+            std::array::from_fn(|key| keys[key] || buttons[key])
+        };
+
+        emulator.set_keys(bindings::game_keypad(keys, rebind_menu.as_ref()));
+
+        pending_time =
+            (pending_time + get_frame_time()).min(FRAME_TIME * MAX_FRAMES_PER_RENDER as f32);
+
+        // The game is paused while rebinding, so that the keys pressed don't reach it.
+        while !done && rebind_menu.is_none() && pending_time >= FRAME_TIME {
+            pending_time -= FRAME_TIME;
+
+            let status = emulator
+                .run_frame()
+                .unwrap_or_else(|e| fail(format!("The program stopped: {e}")));
+
+            done = status == EmulatorStatus::Done;
+        }
+
+        #[cfg(feature = "audio")]
+        {
+            if is_key_pressed(MUTE_KEY) {
+                speaker.toggle_mute();
+            }
+
+            speaker.update(&emulator).await;
+        }
+
+        clear_background(palette[0]);
+
+        let display = &emulator.display;
+        let dx = screen_width() / display.width() as f32;
+        let dy = screen_height() / display.height() as f32;
+
+        for y in 0..display.height() {
+            for x in 0..display.width() {
+                let pixel = display.get(x, y) as usize;
+                if pixel == 0 {
+                    continue;
+                }
+
+                draw_rectangle(x as f32 * dx, y as f32 * dy, dx, dy, palette[pixel]);
+            }
+        }
+
+        if let Some(menu) = &rebind_menu {
+            draw_rectangle(
+                0.0,
+                0.0,
+                screen_width(),
+                screen_height(),
+                Color::new(0.0, 0.0, 0.0, 0.8),
+            );
+            draw_text(
+                format!("Press the key for {:X} (Escape to cancel)", menu.next_key()),
+                20.0,
+                screen_height() / 2.0,
+                32.0,
+                WHITE,
+            );
         }
 
         next_frame().await
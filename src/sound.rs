@@ -0,0 +1,147 @@
+use gr8::emulator::{
+    AUDIO_PATTERN_BITS, AUDIO_PATTERN_SIZE, Beeper, Emulator, generate_samples, playback_rate,
+};
+use macroquad::audio::{PlaySoundParams, Sound, load_sound_from_bytes, play_sound, stop_sound};
+
+const SAMPLE_RATE: u32 = 44100;
+/// Roughly how long the looped beep lasts, so that it holds a whole number of periods.
+const BEEP_LOOP_SECONDS: f32 = 0.1;
+
+/// What the speaker loops: the XO-Chip pattern at its pitch, or the beep when there is none.
+#[derive(Debug, Clone, PartialEq)]
+enum Source {
+    Pattern([u8; AUDIO_PATTERN_SIZE], u8),
+    Beep,
+}
+
+/// Plays the emulator's sound through macroquad while the sound timer is running.
+pub struct Speaker {
+    beeper: Beeper,
+    sound: Option<Sound>,
+    source: Option<Source>,
+    playing: bool,
+}
+
+impl Speaker {
+    pub fn new(beeper: Beeper) -> Self {
+        Speaker {
+            beeper,
+            sound: None,
+            source: None,
+            playing: false,
+        }
+    }
+
+    pub fn toggle_mute(&mut self) {
+        self.beeper.toggle_mute();
+    }
+
+    pub async fn update(&mut self, emulator: &Emulator) {
+        if self.beeper.muted || emulator.sound_timer() == 0 {
+            self.stop();
+            return;
+        }
+
+        let source = match emulator.audio_pattern() {
+            Some(pattern) => Source::Pattern(*pattern, emulator.pitch()),
+            None => Source::Beep,
+        };
+
+        if self.source.as_ref() != Some(&source) {
+            self.stop();
+            self.sound = load_sound_from_bytes(&self.wav(emulator, &source))
+                .await
+                .ok();
+            self.source = Some(source);
+        }
+
+        if let (Some(sound), false) = (&self.sound, self.playing) {
+            play_sound(
+                sound,
+                PlaySoundParams {
+                    looped: true,
+                    volume: 1.0,
+                },
+            );
+            self.playing = true;
+        }
+    }
+
+    fn stop(&mut self) {
+        if let (Some(sound), true) = (&self.sound, self.playing) {
+            stop_sound(sound);
+        }
+        self.playing = false;
+    }
+
+    /// Renders one loop of the source as a WAV file.
+    fn wav(&self, emulator: &Emulator, source: &Source) -> Vec<u8> {
+        let samples = match source {
+            Source::Pattern(..) => {
+                let seconds = AUDIO_PATTERN_BITS as f32 / playback_rate(emulator.pitch());
+                let mut samples = vec![0.0; sample_count(seconds)];
+
+                generate_samples(emulator, SAMPLE_RATE, 0.0, &mut samples);
+                samples.iter_mut().for_each(|s| *s *= self.beeper.volume);
+                samples
+            }
+            Source::Beep => {
+                let periods = (self.beeper.frequency * BEEP_LOOP_SECONDS).round().max(1.0);
+                let mut samples = vec![0.0; sample_count(periods / self.beeper.frequency)];
+
+                self.beeper
+                    .generate(emulator, SAMPLE_RATE, 0.0, &mut samples);
+                samples
+            }
+        };
+
+        encode_wav(&samples, SAMPLE_RATE)
+    }
+}
+
+fn sample_count(seconds: f32) -> usize {
+    (seconds * SAMPLE_RATE as f32).round().max(1.0) as usize
+}
+
+/// Encodes samples in [-1.0, 1.0] as a mono 16-bit PCM WAV file.
+fn encode_wav(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    let data_size = samples.len() as u32 * 2;
+    let mut wav = Vec::with_capacity(44 + data_size as usize);
+
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_size).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_size.to_le_bytes());
+
+    for sample in samples {
+        let sample = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+
+    wav
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_a_mono_16_bit_wav() {
+        let wav = encode_wav(&[1.0, -1.0, 0.0], 8000);
+
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(u32::from_le_bytes(wav[24..28].try_into().unwrap()), 8000);
+        assert_eq!(&wav[36..40], b"data");
+        assert_eq!(u32::from_le_bytes(wav[40..44].try_into().unwrap()), 6);
+        assert_eq!(wav[44..], [0xFF, 0x7F, 0x01, 0x80, 0x00, 0x00]);
+    }
+}